@@ -57,6 +57,12 @@ impl Color {
         }
     }
 
+    /// Perceived brightness of a linear sRGB color, following Rec. 709
+    #[inline]
+    pub fn luminance(&self) -> Float {
+        0.2126 * self.data.x + 0.7152 * self.data.y + 0.0722 * self.data.z
+    }
+
     /// Converts the color from [0, 1] colorspace to 2.2 gamma corrected 8 bit RGB
     pub fn to_gamma_corrected_bytes(&self) -> [u8; 3] {
         let buffer = self.data;
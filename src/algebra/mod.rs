@@ -2,6 +2,7 @@ use super::*;
 use nalgebra::Vector3;
 
 pub mod color;
+pub mod normal;
 pub mod ops;
 pub mod point;
 pub mod vector;
@@ -20,3 +21,10 @@ pub struct Point {
 pub struct Vector {
     pub data: Vector3<Float>,
 }
+
+/// A surface normal, which has to be transformed by the inverse-transpose so that it stays orthogonal
+/// to the surface under non-uniform scaling and shearing
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Normal {
+    pub data: Vector3<Float>,
+}
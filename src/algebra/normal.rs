@@ -0,0 +1,23 @@
+use super::*;
+
+impl Normal {
+    #[inline]
+    pub const fn new(x: Float, y: Float, z: Float) -> Self {
+        Self {
+            data: Vector3::new(x, y, z),
+        }
+    }
+
+    #[inline]
+    pub fn normalize(&self) -> Self {
+        Self {
+            data: self.data.normalize(),
+        }
+    }
+
+    #[inline]
+    /// Returns the unified representation of this normal, which like a vector isn't affected by translations
+    pub fn unified(&self) -> nalgebra::Vector4<Float> {
+        nalgebra::Vector4::<Float>::new(self.data.x, self.data.y, self.data.z, 0.0)
+    }
+}
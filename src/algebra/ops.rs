@@ -331,3 +331,26 @@ impl From<Color> for Vector {
         Self { data: value.data }
     }
 }
+
+// Normal operations
+impl Neg for Normal {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::Output { data: -self.data }
+    }
+}
+
+impl From<Vector> for Normal {
+    #[inline]
+    fn from(value: Vector) -> Self {
+        Self { data: value.data }
+    }
+}
+
+impl From<Normal> for Vector {
+    #[inline]
+    fn from(value: Normal) -> Self {
+        Self { data: value.data }
+    }
+}
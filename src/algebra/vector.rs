@@ -99,6 +99,19 @@ impl Vector {
         *self - 2.0 * normal.dot(self) * normal
     }
 
+    /// Assuming this vector is normalized, returns two unit vectors that complete it to a right handed
+    /// orthonormal basis, based on Duff et al. (2017)
+    pub fn orthonormal_basis(&self) -> (Self, Self) {
+        let sign = (1.0 as Float).copysign(self.z);
+        let a = -1.0 / (sign + self.z);
+        let b = self.x * self.y * a;
+
+        (
+            Self::new(1.0 + sign * self.x * self.x * a, sign * b, -sign * self.x),
+            Self::new(b, sign + self.y * self.y * a, -self.y),
+        )
+    }
+
     #[inline]
     /// Returns the unified representation of this vector
     pub fn unified(&self) -> nalgebra::Vector4<Float> {
@@ -182,10 +182,7 @@ impl BoundingHierarchy {
 
 impl Geometry for BoundingHierarchy {
     fn collide(&self, ray: &Ray, t_range: Range) -> Option<geometry::Collision> {
-        let mut range = match self.bounds.check_intersection(ray, t_range) {
-            Some(range) => range,
-            None => return None,
-        };
+        let mut range = self.bounds.check_intersection(ray, t_range)?;
 
         match &self.data {
             HierarchyNode::Leaf(geometry) => geometry.collide(ray, t_range),
@@ -3,6 +3,7 @@ use std::cmp::Ordering;
 use super::*;
 use math::ZERO_TOL;
 use render::Ray;
+use transform::Transform;
 
 pub mod hierarchy;
 
@@ -34,6 +35,35 @@ impl BoundingBox {
         bounds
     }
 
+    /// Builds the smallest box containing all of the points.
+    /// Will error out if there are no points
+    pub fn from_points(points: &[Point]) -> Self {
+        assert! { !points.is_empty() }
+
+        points
+            .iter()
+            .map(|point| Self::from_extrema(*point, *point))
+            .reduce(|acc, elem| acc.union(&elem))
+            .expect("There is at least one point")
+    }
+
+    /// Returns the eight corners of the box
+    pub fn corners(&self) -> [Point; 8] {
+        let mut corners = [Point::zeros(); 8];
+
+        for (index, corner) in corners.iter_mut().enumerate() {
+            let pick = |range: Range, bit: usize| if index & bit == 0 { range.0 } else { range.1 };
+            *corner = Point::new(pick(self.x, 1), pick(self.y, 2), pick(self.z, 4));
+        }
+
+        corners
+    }
+
+    /// Returns the smallest box containing this one after it's mapped by the transform
+    pub fn transformed(&self, transform: Transform) -> Self {
+        Self::from_points(&self.corners().map(|corner| transform * corner))
+    }
+
     pub fn union(&self, other: &Self) -> Self {
         Self {
             x: self.x.union(other.x),
@@ -0,0 +1,142 @@
+use super::*;
+use material::Material;
+use std::sync::Arc;
+
+/// A circular cone, which in local space has its unit radius base at z = 0 and its apex at (0, 0, 1)
+#[derive(Clone)]
+pub struct Cone {
+    transform: Transform,
+    bounds: BoundingBox,
+    material: Arc<dyn Material>,
+    is_capped: bool,
+}
+
+impl Cone {
+    /// Builds a cone closed at its base, given the center of the base, the apex and the base radius.
+    /// Will error out if the apex is at the center of the base or the radius isn't positive
+    pub fn new(base: Point, apex: Point, radius: Float, material: Arc<dyn Material>) -> Arc<Self> {
+        Self::build(base, apex, radius, true, material)
+    }
+
+    /// Builds a cone open at its base.
+    /// Will error out if the apex is at the center of the base or the radius isn't positive
+    pub fn uncapped(
+        base: Point,
+        apex: Point,
+        radius: Float,
+        material: Arc<dyn Material>,
+    ) -> Arc<Self> {
+        Self::build(base, apex, radius, false, material)
+    }
+
+    fn build(
+        base: Point,
+        apex: Point,
+        radius: Float,
+        is_capped: bool,
+        material: Arc<dyn Material>,
+    ) -> Arc<Self> {
+        assert! { radius > 0.0 }
+
+        let axis = apex - base;
+        assert! { axis.norm_squared() > ZERO_TOL }
+
+        let (u, v) = axis.normalize().orthonormal_basis();
+        let transform =
+            Transform::make_basis(radius * u, radius * v, axis) * Transform::make_origin(base);
+
+        let local_bounds = BoundingBox::new(Range(-1.0, 1.0), Range(-1.0, 1.0), Range(0.0, 1.0));
+
+        Arc::new(Self {
+            transform,
+            bounds: local_bounds.transformed(transform.inverse()),
+            material,
+            is_capped,
+        })
+    }
+}
+
+impl Geometry for Cone {
+    fn collide(&self, ray: &Ray, t_range: Range) -> Option<Collision> {
+        let (original_ray, ray) = (ray, self.transform * (*ray));
+
+        // Keeps the closest t found so far, and whether it's on the side or on the base
+        let mut closest: Option<(Float, bool)> = None;
+        let mut consider = |t: Float, is_side: bool| {
+            if t_range.contains(t) && closest.is_none_or(|(closest_t, _)| t < closest_t) {
+                closest = Some((t, is_side));
+            }
+        };
+
+        // The side is the part of x² + y² = (1 - z)² with z in [0, 1]
+        let Ray {
+            origin, direction, ..
+        } = ray;
+        let height = 1.0 - origin.z;
+        let a = direction.x * direction.x + direction.y * direction.y - direction.z * direction.z;
+        let h = -(origin.x * direction.x + origin.y * direction.y + height * direction.z);
+        let c = origin.x * origin.x + origin.y * origin.y - height * height;
+
+        let roots = if a.abs() > ZERO_TOL {
+            let delta = h * h - a * c;
+            if delta >= 0.0 {
+                let delta_sqrt = delta.sqrt();
+                [Some((h - delta_sqrt) / a), Some((h + delta_sqrt) / a)]
+            } else {
+                [None, None]
+            }
+        } else if h.abs() > ZERO_TOL {
+            // Rays parallel to the side only cross it once
+            [Some(c / (2.0 * h)), None]
+        } else {
+            [None, None]
+        };
+
+        for root in roots.into_iter().flatten() {
+            if Range(0.0, 1.0).contains(ray.at(root).z) {
+                consider(root, true);
+            }
+        }
+
+        if self.is_capped {
+            if let Some(t) = collide_unit_disk(&ray, 0.0) {
+                consider(t, false);
+            }
+        }
+
+        let (t, is_side) = closest?;
+        let glancing_point = ray.at(t);
+        let (uv, tangent, bitangent) = polar_coordinates(glancing_point);
+
+        let (normal, uv, bitangent) = if is_side {
+            let mut normal =
+                Vector::new(glancing_point.x, glancing_point.y, 1.0 - glancing_point.z);
+            // The side has no normal at the apex, where the axis is the closest thing to one
+            if normal.norm_squared() < ZERO_TOL * ZERO_TOL {
+                normal = Vector::z();
+            }
+            let slope = Vector::z() - bitangent;
+            (normal, UV(uv.0, glancing_point.z), slope.normalize())
+        } else {
+            (-Vector::z(), uv, bitangent)
+        };
+
+        let mut collision = Collision {
+            point: glancing_point,
+            normal,
+            t,
+            uv,
+            tangent,
+            bitangent,
+            is_front_facing: true,
+            material: self.material.clone(),
+        };
+        collision.apply(original_ray, self.transform);
+
+        Some(collision)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        self.bounds
+    }
+}
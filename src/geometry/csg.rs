@@ -0,0 +1,229 @@
+use super::*;
+use std::sync::Arc;
+
+/// Boolean operations on solids
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operation {
+    Union,
+    Intersection,
+    /// Everything in the first operand that isn't in the second one
+    Difference,
+}
+
+impl Operation {
+    fn is_inside(&self, in_left: bool, in_right: bool) -> bool {
+        match self {
+            Operation::Union => in_left || in_right,
+            Operation::Intersection => in_left && in_right,
+            Operation::Difference => in_left && !in_right,
+        }
+    }
+}
+
+/// Combines two closed geometries into a new solid. Collisions keep the normal and material of the operand
+/// whose surface was hit, with the normals of subtracted surfaces facing out of the result.
+#[derive(Clone)]
+pub struct Csg {
+    left: WorldObject,
+    right: WorldObject,
+    operation: Operation,
+    bounds: BoundingBox,
+}
+
+impl Csg {
+    pub fn new(left: WorldObject, right: WorldObject, operation: Operation) -> Arc<Self> {
+        let bounds = match operation {
+            Operation::Union => left.bounding_box().union(&right.bounding_box()),
+            Operation::Intersection | Operation::Difference => left.bounding_box(),
+        };
+
+        Arc::new(Self {
+            left,
+            right,
+            operation,
+            bounds,
+        })
+    }
+
+    pub fn union(left: WorldObject, right: WorldObject) -> Arc<Self> {
+        Self::new(left, right, Operation::Union)
+    }
+
+    pub fn intersection(left: WorldObject, right: WorldObject) -> Arc<Self> {
+        Self::new(left, right, Operation::Intersection)
+    }
+
+    pub fn difference(left: WorldObject, right: WorldObject) -> Arc<Self> {
+        Self::new(left, right, Operation::Difference)
+    }
+}
+
+/// A point along the ray where it enters or exits one of the operands
+struct Event {
+    is_left: bool,
+    is_entry: bool,
+    collision: Collision,
+}
+
+impl Csg {
+    /// Every boundary of the operands along the ray from the start of the range on, in order, and whether
+    /// the ray starts inside each of them. Operands are searched past the end of the range, since telling
+    /// whether the ray starts inside them takes finding where it exits, which may lie beyond.
+    fn events(&self, ray: &Ray, t_range: Range) -> (Vec<Event>, bool, bool) {
+        let mut events = vec![];
+        let (mut in_left, mut in_right) = (false, false);
+        let operand_range = Range(t_range.0, Float::INFINITY);
+        for (is_left, operand) in [(true, &self.left), (false, &self.right)] {
+            for Span { entry, exit } in operand.intervals(ray, operand_range) {
+                match entry {
+                    Some(collision) => events.push(Event {
+                        is_left,
+                        is_entry: true,
+                        collision,
+                    }),
+                    // The ray starts inside the operand
+                    None if is_left => in_left = true,
+                    None => in_right = true,
+                }
+                if let Some(collision) = exit {
+                    events.push(Event {
+                        is_left,
+                        is_entry: false,
+                        collision,
+                    });
+                }
+            }
+        }
+        events.sort_unstable_by(|a, b| a.collision.t.total_cmp(&b.collision.t));
+
+        (events, in_left, in_right)
+    }
+}
+
+impl Geometry for Csg {
+    /// Stops at the first boundary of the result, without building the spans past it
+    fn collide(&self, ray: &Ray, t_range: Range) -> Option<Collision> {
+        let (events, mut in_left, mut in_right) = self.events(ray, t_range);
+
+        for event in events {
+            if t_range.not_contains(event.collision.t) {
+                return None;
+            }
+
+            let was_inside = self.operation.is_inside(in_left, in_right);
+            if event.is_left {
+                in_left = event.is_entry;
+            } else {
+                in_right = event.is_entry;
+            }
+            let is_inside = self.operation.is_inside(in_left, in_right);
+
+            if was_inside != is_inside {
+                let mut collision = event.collision;
+                collision.is_front_facing = is_inside;
+                return Some(collision);
+            }
+        }
+
+        None
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        self.bounds
+    }
+
+    /// Sweeps along the boundaries of both operands, keeping the ones where being inside the result changes
+    fn intervals(&self, ray: &Ray, t_range: Range) -> Vec<Span> {
+        let (events, mut in_left, mut in_right) = self.events(ray, t_range);
+
+        let mut spans = vec![];
+        let mut entry = self.operation.is_inside(in_left, in_right).then_some(None);
+        for event in events {
+            // Past the range, a span that's still open is cut short
+            if t_range.not_contains(event.collision.t) {
+                break;
+            }
+
+            let was_inside = self.operation.is_inside(in_left, in_right);
+            if event.is_left {
+                in_left = event.is_entry;
+            } else {
+                in_right = event.is_entry;
+            }
+            let is_inside = self.operation.is_inside(in_left, in_right);
+
+            // Collisions already face the ray, so only their facing has to be updated
+            let mut collision = event.collision;
+            collision.is_front_facing = is_inside;
+
+            match (was_inside, is_inside) {
+                (false, true) => entry = Some(Some(collision)),
+                (true, false) => spans.push(Span {
+                    entry: entry.take().flatten(),
+                    exit: Some(collision),
+                }),
+                _ => {}
+            }
+        }
+
+        if let Some(entry) = entry {
+            spans.push(Span { entry, exit: None });
+        }
+
+        spans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use material::diffuse::Lambertian;
+    use sphere::Ellipsoid;
+
+    fn ball(center: Point, radius: Float) -> WorldObject {
+        let material = Lambertian::new(Color::new(0.5, 0.5, 0.5));
+        Ellipsoid::new(center, Vector::new(radius, radius, radius), material)
+    }
+
+    /// A big ball with a small one carved out of it, and a ray from inside the big one towards the hole
+    fn setup() -> (Arc<Csg>, Ray) {
+        let csg = Csg::difference(
+            ball(Point::new(0.0, 0.0, 0.0), 10.0),
+            ball(Point::new(5.0, 0.0, 0.0), 1.0),
+        );
+        let ray = Ray::new(Point::new(0.0, 0.0, 0.0), Vector::new(1.0, 0.0, 0.0));
+
+        (csg, ray)
+    }
+
+    #[test]
+    fn collide_with_narrowed_range() {
+        let (csg, ray) = setup();
+
+        let collision = csg.collide(&ray, Range(0.001, Float::INFINITY)).unwrap();
+        assert!((collision.t - 4.0).abs() < 1e-3);
+        assert!(!collision.is_front_facing);
+
+        // The exit from the big ball lies past the end of the range
+        let collision = csg.collide(&ray, Range(0.001, 4.5)).unwrap();
+        assert!((collision.t - 4.0).abs() < 1e-3);
+
+        assert!(csg.collide(&ray, Range(0.001, 3.5)).is_none());
+    }
+
+    #[test]
+    fn intervals_with_narrowed_range() {
+        let (csg, ray) = setup();
+
+        let spans = csg.intervals(&ray, Range(0.001, 4.5));
+        assert_eq!(spans.len(), 1);
+        assert!(spans[0].entry.is_none());
+        assert!((spans[0].exit.as_ref().unwrap().t - 4.0).abs() < 1e-3);
+
+        // Starting inside the hole, the ray enters the solid again and leaves the range inside it
+        let spans = csg.intervals(&ray, Range(4.5, 8.0));
+        assert_eq!(spans.len(), 1);
+        assert!((spans[0].entry.as_ref().unwrap().t - 6.0).abs() < 1e-3);
+        assert!(spans[0].exit.is_none());
+    }
+}
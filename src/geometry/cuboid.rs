@@ -0,0 +1,116 @@
+use super::*;
+use material::Material;
+use std::sync::Arc;
+
+/// A box, which in local space is the unit cube [0, 1]³
+#[derive(Clone)]
+pub struct Cuboid {
+    transform: Transform,
+    bounds: BoundingBox,
+    material: Arc<dyn Material>,
+}
+
+impl Cuboid {
+    /// Builds an axis-aligned box given two opposite corners.
+    /// Will error out if the box is flat along any axis
+    pub fn new(p: Point, q: Point, material: Arc<dyn Material>) -> Arc<Self> {
+        let corner = Point::new(p.x.min(q.x), p.y.min(q.y), p.z.min(q.z));
+        let size = Vector::new((p.x - q.x).abs(), (p.y - q.y).abs(), (p.z - q.z).abs());
+
+        Self::oriented(
+            corner,
+            size.x * Vector::x(),
+            size.y * Vector::y(),
+            size.z * Vector::z(),
+            material,
+        )
+    }
+
+    /// Builds a box given a corner and the three edges leaving it, which needn't be orthogonal.
+    /// Will error out if the edges are LD
+    pub fn oriented(
+        corner: Point,
+        u: Vector,
+        v: Vector,
+        w: Vector,
+        material: Arc<dyn Material>,
+    ) -> Arc<Self> {
+        let transform = Transform::make_basis(u, v, w) * Transform::make_origin(corner);
+        let unit = Range(0.0, 1.0);
+
+        Arc::new(Self {
+            transform,
+            bounds: BoundingBox::new(unit, unit, unit).transformed(transform.inverse()),
+            material,
+        })
+    }
+}
+
+impl Geometry for Cuboid {
+    fn collide(&self, ray: &Ray, t_range: Range) -> Option<Collision> {
+        let (original_ray, ray) = (ray, self.transform * (*ray));
+
+        // Slab test, keeping track of which axis the ray enters and exits through
+        let (mut entry, mut exit) = ((Float::NEG_INFINITY, 0), (Float::INFINITY, 0));
+        for axis in 0..3 {
+            let coefficient = 1.0 / ray.direction[axis];
+
+            let mut t0 = -ray.origin[axis] * coefficient;
+            let mut t1 = (1.0 - ray.origin[axis]) * coefficient;
+            if t1 < t0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+
+            if t0 > entry.0 {
+                entry = (t0, axis);
+            }
+            if t1 < exit.0 {
+                exit = (t1, axis);
+            }
+        }
+
+        if entry.0 > exit.0 {
+            return None;
+        }
+
+        let (t, axis) = if t_range.contains(entry.0) {
+            entry
+        } else if t_range.contains(exit.0) {
+            exit
+        } else {
+            return None;
+        };
+
+        let glancing_point = ray.at(t);
+        let mut normal = Vector::zeros();
+        normal[axis] = if glancing_point[axis] > 0.5 {
+            1.0
+        } else {
+            -1.0
+        };
+
+        // Each face is parametrized by the two remaining axes, in cyclic order
+        let (u_axis, v_axis) = ((axis + 1) % 3, (axis + 2) % 3);
+        let (mut tangent, mut bitangent) = (Vector::zeros(), Vector::zeros());
+        tangent[u_axis] = 1.0;
+        bitangent[v_axis] = 1.0;
+
+        let mut collision = Collision {
+            point: glancing_point,
+            normal,
+            t,
+            uv: UV(glancing_point[u_axis], glancing_point[v_axis]),
+            tangent,
+            bitangent,
+            is_front_facing: true,
+            material: self.material.clone(),
+        };
+        collision.apply(original_ray, self.transform);
+
+        Some(collision)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        self.bounds
+    }
+}
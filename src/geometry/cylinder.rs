@@ -0,0 +1,127 @@
+use super::*;
+use material::Material;
+use std::sync::Arc;
+
+/// A circular cylinder, which in local space has unit radius around the z axis and spans z in [0, 1]
+#[derive(Clone)]
+pub struct Cylinder {
+    transform: Transform,
+    bounds: BoundingBox,
+    material: Arc<dyn Material>,
+    is_capped: bool,
+}
+
+impl Cylinder {
+    /// Builds a closed cylinder given the centers of its bases and its radius.
+    /// Will error out if the centers coincide or the radius isn't positive
+    pub fn new(base: Point, top: Point, radius: Float, material: Arc<dyn Material>) -> Arc<Self> {
+        Self::build(base, top, radius, true, material)
+    }
+
+    /// Builds a cylinder without caps, i.e.: a tube.
+    /// Will error out if the centers coincide or the radius isn't positive
+    pub fn uncapped(
+        base: Point,
+        top: Point,
+        radius: Float,
+        material: Arc<dyn Material>,
+    ) -> Arc<Self> {
+        Self::build(base, top, radius, false, material)
+    }
+
+    fn build(
+        base: Point,
+        top: Point,
+        radius: Float,
+        is_capped: bool,
+        material: Arc<dyn Material>,
+    ) -> Arc<Self> {
+        assert! { radius > 0.0 }
+
+        let axis = top - base;
+        assert! { axis.norm_squared() > ZERO_TOL }
+
+        let (u, v) = axis.normalize().orthonormal_basis();
+        let transform =
+            Transform::make_basis(radius * u, radius * v, axis) * Transform::make_origin(base);
+
+        let local_bounds = BoundingBox::new(Range(-1.0, 1.0), Range(-1.0, 1.0), Range(0.0, 1.0));
+
+        Arc::new(Self {
+            transform,
+            bounds: local_bounds.transformed(transform.inverse()),
+            material,
+            is_capped,
+        })
+    }
+}
+
+impl Geometry for Cylinder {
+    fn collide(&self, ray: &Ray, t_range: Range) -> Option<Collision> {
+        let (original_ray, ray) = (ray, self.transform * (*ray));
+
+        // Keeps the closest t found so far, and whether it's on the side or on a cap
+        let mut closest: Option<(Float, bool)> = None;
+        let mut consider = |t: Float, is_side: bool| {
+            if t_range.contains(t) && closest.is_none_or(|(closest_t, _)| t < closest_t) {
+                closest = Some((t, is_side));
+            }
+        };
+
+        let Ray {
+            origin, direction, ..
+        } = ray;
+        let a = direction.x * direction.x + direction.y * direction.y;
+        let h = -(origin.x * direction.x + origin.y * direction.y);
+        let c = origin.x * origin.x + origin.y * origin.y - 1.0;
+
+        let delta = h * h - a * c;
+        if a > ZERO_TOL && delta >= 0.0 {
+            let delta_sqrt = delta.sqrt();
+            for root in [(h - delta_sqrt) / a, (h + delta_sqrt) / a] {
+                if Range(0.0, 1.0).contains(ray.at(root).z) {
+                    consider(root, true);
+                }
+            }
+        }
+
+        if self.is_capped {
+            for height in [0.0, 1.0] {
+                if let Some(t) = collide_unit_disk(&ray, height) {
+                    consider(t, false);
+                }
+            }
+        }
+
+        let (t, is_side) = closest?;
+        let glancing_point = ray.at(t);
+        let (uv, tangent, bitangent) = polar_coordinates(glancing_point);
+
+        let (normal, uv, bitangent) = if is_side {
+            let normal = Vector::new(glancing_point.x, glancing_point.y, 0.0);
+            (normal, UV(uv.0, glancing_point.z), Vector::z())
+        } else if glancing_point.z > 0.5 {
+            (Vector::z(), uv, bitangent)
+        } else {
+            (-Vector::z(), uv, bitangent)
+        };
+
+        let mut collision = Collision {
+            point: glancing_point,
+            normal,
+            t,
+            uv,
+            tangent,
+            bitangent,
+            is_front_facing: true,
+            material: self.material.clone(),
+        };
+        collision.apply(original_ray, self.transform);
+
+        Some(collision)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        self.bounds
+    }
+}
@@ -0,0 +1,138 @@
+use super::*;
+use io::volume::{self, Grid};
+use io::LoadError;
+use std::path::Path;
+use std::sync::Arc;
+use texture::{NoisePattern, Perlin};
+
+/// A density that varies in space, filling heterogeneous media
+pub trait Density: std::marker::Send + std::marker::Sync {
+    /// Probability of colliding per unit of length at a point
+    fn density(&self, point: &Point) -> Float;
+
+    /// Upper bound of the density everywhere, used to sample tentative collisions
+    fn majorant(&self) -> Float;
+}
+
+/// Procedural density following a Perlin noise pattern, good for clouds and smoke
+#[derive(Debug, Clone)]
+pub struct NoiseDensity {
+    noise: Perlin,
+    maximum: Float,
+    scale: Float,
+    pattern: NoisePattern,
+}
+
+impl NoiseDensity {
+    /// `maximum` is the density where the pattern is at its peak, and `scale` is the frequency of the noise.
+    /// Will error out if the maximum is negative
+    pub fn new(maximum: Float, scale: Float, pattern: NoisePattern) -> Arc<Self> {
+        assert! { maximum >= 0.0 }
+
+        Arc::new(Self {
+            noise: Perlin::new(),
+            maximum,
+            scale,
+            pattern,
+        })
+    }
+}
+
+impl Density for NoiseDensity {
+    fn density(&self, point: &Point) -> Float {
+        // Smooth noise may stray slightly out of [0, 1], which would break the majorant
+        let intensity = self.pattern.intensity(&self.noise, point, self.scale);
+        self.maximum * intensity.clamp(0.0, 1.0)
+    }
+
+    fn majorant(&self) -> Float {
+        self.maximum
+    }
+}
+
+/// Density sampled on a voxel grid stretched over a box, trilinearly interpolated between voxels and zero
+/// outside of the box
+#[derive(Debug, Clone)]
+pub struct GridDensity {
+    grid: Grid,
+    bounds: BoundingBox,
+    scale: Float,
+    majorant: Float,
+}
+
+impl GridDensity {
+    /// Voxels are placed at the corners of a regular lattice spanning the box, and their values are
+    /// multiplied by `scale`.
+    /// Will error out if the grid has negative values or the scale is negative
+    pub fn new(grid: Grid, bounds: BoundingBox, scale: Float) -> Arc<Self> {
+        assert! { scale >= 0.0 }
+        assert! { grid.values().iter().all(|&value| value >= 0.0) }
+
+        let majorant = scale * grid.values().iter().copied().fold(0.0, Float::max);
+
+        Arc::new(Self {
+            grid,
+            bounds,
+            scale,
+            majorant,
+        })
+    }
+
+    /// Loads the grid from a raw file, as described in `io::volume::load`
+    pub fn load(
+        path: impl AsRef<Path>,
+        bounds: BoundingBox,
+        scale: Float,
+    ) -> Result<Arc<Self>, LoadError> {
+        Ok(Self::new(volume::load(path)?, bounds, scale))
+    }
+}
+
+impl Density for GridDensity {
+    fn density(&self, point: &Point) -> Float {
+        let dimensions = self.grid.dimensions();
+
+        // Continuous voxel coordinates of the point, split into a voxel and the offset from it
+        let mut voxel = [0; 3];
+        let mut offset = [0.0; 3];
+        for (axis, Range(min, max)) in self.bounds.axes().into_iter().enumerate() {
+            let relative = (point[axis] - min) / (max - min);
+            if !(0.0..=1.0).contains(&relative) {
+                return 0.0;
+            }
+
+            let last = dimensions[axis] - 1;
+            let coordinate = relative * last as Float;
+            voxel[axis] = (coordinate.floor() as usize).min(last.saturating_sub(1));
+            offset[axis] = if last == 0 {
+                0.0
+            } else {
+                coordinate - voxel[axis] as Float
+            };
+        }
+
+        let mut accumulator = 0.0;
+        for corner in 0..8 {
+            let mut weight = 1.0;
+            let mut index = voxel;
+            for axis in 0..3 {
+                if corner & (1 << axis) == 0 {
+                    weight *= 1.0 - offset[axis];
+                } else {
+                    weight *= offset[axis];
+                    index[axis] = (index[axis] + 1).min(dimensions[axis] - 1);
+                }
+            }
+
+            if weight > 0.0 {
+                accumulator += weight * self.grid.get(index[0], index[1], index[2]);
+            }
+        }
+
+        self.scale * accumulator
+    }
+
+    fn majorant(&self) -> Float {
+        self.majorant
+    }
+}
@@ -42,6 +42,16 @@ impl Parallelogram {
         })
     }
 
+    /// Returns the vectors defining the sides of the parallelogram
+    fn sides(&self) -> (Vector, Vector) {
+        (self.transform / Vector::x(), self.transform / Vector::y())
+    }
+
+    fn area(&self) -> Float {
+        let (u, v) = self.sides();
+        u.cross(&v).norm()
+    }
+
     fn get_bounding_box(corner: Point, u: Vector, v: Vector) -> BoundingBox {
         let bounds_a = BoundingBox::from_extrema(corner, corner + u + v);
         let bounds_b = BoundingBox::from_extrema(corner + u, corner + v);
@@ -76,10 +86,95 @@ impl Geometry for Parallelogram {
             point: glancing_point,
             normal,
             t,
+            uv: UV(glancing_point.x, glancing_point.y),
+            tangent: Vector::x(),
+            bitangent: Vector::y(),
             is_front_facing,
             material: self.material.clone(),
         };
-        collision.apply(&original_ray, self.transform);
+        collision.apply(original_ray, self.transform);
+
+        Some(collision)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        self.bounds
+    }
+
+    fn sample_surface(&self, _time: Float) -> Option<SurfaceSample> {
+        let local = Point::new(random::random_float(), random::random_float(), 0.0);
+        let normal = Vector::from(self.transform / Normal::new(0.0, 0.0, 1.0));
+
+        Some(SurfaceSample {
+            point: self.transform / local,
+            normal: normal.normalize(),
+            pdf: 1.0 / self.area(),
+        })
+    }
+
+    fn surface_pdf(&self, _point: &Point, _normal: &Vector, _time: Float) -> Float {
+        1.0 / self.area()
+    }
+}
+
+#[derive(Clone)]
+pub struct Disk {
+    transform: Transform,
+    bounds: BoundingBox,
+    material: Arc<dyn Material>,
+    radius: Float,
+}
+
+impl Disk {
+    /// Creates a disk given its center, the normal of its front face and its radius.
+    /// Will error out if the normal is the zero vector or the radius isn't positive
+    pub fn new(
+        center: Point,
+        normal: Vector,
+        radius: Float,
+        material: Arc<dyn Material>,
+    ) -> Arc<Self> {
+        assert! { normal.norm_squared() > ZERO_TOL }
+        assert! { radius > 0.0 }
+
+        let normal = normal.normalize();
+        let (u, v) = normal.orthonormal_basis();
+        let transform =
+            Transform::make_basis(radius * u, radius * v, normal) * Transform::make_origin(center);
+
+        let local_bounds = BoundingBox::new(Range(-1.0, 1.0), Range(-1.0, 1.0), Range(0.0, 0.0));
+
+        Arc::new(Self {
+            transform,
+            bounds: local_bounds.transformed(transform.inverse()),
+            material,
+            radius,
+        })
+    }
+}
+
+impl Geometry for Disk {
+    fn collide(&self, ray: &Ray, t_range: Range) -> Option<Collision> {
+        let (original_ray, ray) = (ray, self.transform * (*ray));
+
+        let t = collide_unit_disk(&ray, 0.0)?;
+        if t_range.not_contains(t) {
+            return None;
+        }
+
+        let glancing_point = ray.at(t);
+        let (uv, tangent, bitangent) = polar_coordinates(glancing_point);
+        let mut collision = Collision {
+            point: glancing_point,
+            normal: Vector::z(),
+            t,
+            uv,
+            tangent,
+            bitangent,
+            is_front_facing: true,
+            material: self.material.clone(),
+        };
+        collision.apply(original_ray, self.transform);
 
         Some(collision)
     }
@@ -87,4 +182,21 @@ impl Geometry for Parallelogram {
     fn bounding_box(&self) -> BoundingBox {
         self.bounds
     }
+
+    fn sample_surface(&self, time: Float) -> Option<SurfaceSample> {
+        let radius = random::random_float().sqrt();
+        let phi = 2.0 * math::PI * random::random_float();
+        let local = Point::new(radius * phi.cos(), radius * phi.sin(), 0.0);
+        let normal = Vector::from(self.transform / Normal::new(0.0, 0.0, 1.0));
+
+        Some(SurfaceSample {
+            point: self.transform / local,
+            normal: normal.normalize(),
+            pdf: self.surface_pdf(&local, &normal, time),
+        })
+    }
+
+    fn surface_pdf(&self, _point: &Point, _normal: &Vector, _time: Float) -> Float {
+        1.0 / (math::PI * self.radius * self.radius)
+    }
 }
@@ -0,0 +1,339 @@
+use super::*;
+use io::image::{self, Image};
+use io::LoadError;
+use material::Material;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Terrain given by a grid of elevations, split into two triangles per cell. In local space, sample (x, z)
+/// sits at (x, height, z), and rays walk through the cells they cross, skipping those whose range of
+/// heights they pass above or below.
+#[derive(Clone)]
+pub struct Heightfield {
+    transform: Transform,
+    material: Arc<dyn Material>,
+    dimensions: Dimensions,
+    heights: Vec<Float>,
+    /// Lowest and highest elevation in each cell
+    cell_ranges: Vec<Range>,
+    local_bounds: BoundingBox,
+    bounds: BoundingBox,
+}
+
+impl Heightfield {
+    /// Builds a heightfield from elevations laid out row by row, spanning `size` from `corner`, where
+    /// elevations are multiplied by the y component of the size.
+    /// Will error out if the grid is smaller than 2x2, if the number of elevations doesn't match the
+    /// dimensions or if any size is 0
+    pub fn new(
+        heights: Vec<Float>,
+        dimensions: Dimensions,
+        corner: Point,
+        size: Vector,
+        material: Arc<dyn Material>,
+    ) -> Arc<Self> {
+        let Dimensions(width, depth) = dimensions;
+        assert! { width >= 2 && depth >= 2 }
+        assert_eq! { heights.len(), width * depth }
+
+        let cell_ranges = (0..(depth - 1))
+            .flat_map(|z| (0..(width - 1)).map(move |x| (x, z)))
+            .map(|(x, z)| {
+                let corners = [
+                    heights[x + width * z],
+                    heights[x + 1 + width * z],
+                    heights[x + width * (z + 1)],
+                    heights[x + 1 + width * (z + 1)],
+                ];
+                let min = corners.into_iter().fold(Float::INFINITY, Float::min);
+                let max = corners.into_iter().fold(Float::NEG_INFINITY, Float::max);
+
+                Range(min, max)
+            })
+            .collect();
+
+        let lowest = heights.iter().copied().fold(Float::INFINITY, Float::min);
+        let highest = heights
+            .iter()
+            .copied()
+            .fold(Float::NEG_INFINITY, Float::max);
+        let local_bounds = BoundingBox::new(
+            Range(0.0, (width - 1) as Float),
+            Range(lowest, highest),
+            Range(0.0, (depth - 1) as Float),
+        );
+
+        let placement = Transform::translate(corner.into())
+            * Transform::scale(
+                size.x / (width - 1) as Float,
+                size.y,
+                size.z / (depth - 1) as Float,
+            );
+
+        Arc::new(Self {
+            transform: placement.inverse(),
+            material,
+            dimensions,
+            heights,
+            cell_ranges,
+            local_bounds,
+            bounds: local_bounds.transformed(placement),
+        })
+    }
+
+    /// Builds a heightfield from the luminance of an image, where white is the highest elevation and
+    /// the top of the image lies at the lowest z.
+    /// Will error out if the image is smaller than 2x2
+    pub fn from_image(
+        image: &Image,
+        corner: Point,
+        size: Vector,
+        material: Arc<dyn Material>,
+    ) -> Arc<Self> {
+        let dimensions = image.dimensions();
+        let Dimensions(width, depth) = dimensions;
+
+        let heights = (0..depth)
+            .flat_map(|z| (0..width).map(move |x| (x, z)))
+            .map(|(x, z)| image.get(x, z).luminance())
+            .collect();
+
+        Self::new(heights, dimensions, corner, size, material)
+    }
+
+    /// Loads the elevations from an 8 or 16-bit grayscale PNG, as in `Heightfield::from_image`
+    pub fn load(
+        path: impl AsRef<Path>,
+        corner: Point,
+        size: Vector,
+        material: Arc<dyn Material>,
+    ) -> Result<Arc<Self>, LoadError> {
+        let path = path.as_ref();
+        let image = image::load_png(path)?;
+
+        let Dimensions(width, depth) = image.dimensions();
+        if width < 2 || depth < 2 {
+            return Err(LoadError::format(
+                path,
+                "heightfield needs at least 2x2 samples",
+            ));
+        }
+
+        Ok(Self::from_image(&image, corner, size, material))
+    }
+
+    #[inline]
+    fn height(&self, x: usize, z: usize) -> Float {
+        let Dimensions(width, _) = self.dimensions;
+        self.heights[x + width * z]
+    }
+
+    fn vertex(&self, x: usize, z: usize) -> Point {
+        Point::new(x as Float, self.height(x, z), z as Float)
+    }
+
+    /// Smooth normal at a sample, from central differences of the elevations
+    fn vertex_normal(&self, x: usize, z: usize) -> Vector {
+        let Dimensions(width, depth) = self.dimensions;
+        let (left, right) = (x.saturating_sub(1), (x + 1).min(width - 1));
+        let (back, front) = (z.saturating_sub(1), (z + 1).min(depth - 1));
+
+        let slope_x = (self.height(right, z) - self.height(left, z)) / (right - left) as Float;
+        let slope_z = (self.height(x, front) - self.height(x, back)) / (front - back) as Float;
+
+        Vector::new(-slope_x, 1.0, -slope_z)
+    }
+
+    /// Builds the collision at a point of a cell, interpolating the normals at its corners
+    fn make_collision(
+        &self,
+        original_ray: &Ray,
+        ray: &Ray,
+        cell: (usize, usize),
+        hit: CellHit,
+    ) -> Collision {
+        let CellHit {
+            t,
+            weights,
+            geometric_normal,
+        } = hit;
+        let (x, z) = cell;
+        let samples = [(x, z), (x + 1, z), (x, z + 1), (x + 1, z + 1)];
+
+        let normal = samples
+            .iter()
+            .zip(weights)
+            .fold(Vector::zeros(), |acc, (&(x, z), weight)| {
+                acc + weight * self.vertex_normal(x, z)
+            });
+
+        // Moving along x or z on the surface also changes the elevation, following the normal's slope
+        let tangent = Vector::new(1.0, -normal.x / normal.y, 0.0);
+        let bitangent = Vector::new(0.0, -normal.z / normal.y, 1.0);
+
+        let point = ray.at(t);
+        let Dimensions(width, depth) = self.dimensions;
+
+        // Facing is decided by the triangle that was hit, with the smooth normal only used for shading
+        let mut collision = Collision {
+            point,
+            normal: geometric_normal,
+            t,
+            uv: UV(
+                point.x / (width - 1) as Float,
+                point.z / (depth - 1) as Float,
+            ),
+            tangent,
+            bitangent,
+            is_front_facing: true,
+            material: self.material.clone(),
+        };
+        collision.apply(original_ray, self.transform);
+
+        let shading_normal = Vector::from(self.transform / Normal::from(normal)).normalize();
+        collision.normal = if shading_normal.dot(&collision.normal) < 0.0 {
+            -shading_normal
+        } else {
+            shading_normal
+        };
+
+        collision
+    }
+
+    /// Intersects the two triangles of a cell, returning the closest hit within the range
+    fn collide_cell(&self, ray: &Ray, cell: (usize, usize), t_range: Range) -> Option<CellHit> {
+        let (x, z) = cell;
+        let corners = [
+            self.vertex(x, z),
+            self.vertex(x + 1, z),
+            self.vertex(x, z + 1),
+            self.vertex(x + 1, z + 1),
+        ];
+
+        // Both triangles are wound so that their normals point up
+        let mut closest: Option<CellHit> = None;
+        for [a, b, c] in [[0, 2, 1], [1, 2, 3]] {
+            let Some((t, v, w)) = collide_triangle(ray, corners[a], corners[b], corners[c]) else {
+                continue;
+            };
+            if t_range.not_contains(t) || closest.as_ref().is_some_and(|hit| hit.t <= t) {
+                continue;
+            }
+
+            let mut weights = [0.0; 4];
+            weights[a] = 1.0 - v - w;
+            weights[b] = v;
+            weights[c] = w;
+            closest = Some(CellHit {
+                t,
+                weights,
+                geometric_normal: (corners[b] - corners[a]).cross(&(corners[c] - corners[a])),
+            });
+        }
+
+        closest
+    }
+}
+
+/// A collision with one of the triangles of a cell, in local space
+struct CellHit {
+    t: Float,
+    /// Barycentric weights of the cell's corners
+    weights: [Float; 4],
+    geometric_normal: Vector,
+}
+
+/// Möller-Trumbore intersection, returning t and the barycentric weights of the second and third vertices
+fn collide_triangle(ray: &Ray, a: Point, b: Point, c: Point) -> Option<(Float, Float, Float)> {
+    let (edge_b, edge_c) = (b - a, c - a);
+    let p = ray.direction.cross(&edge_c);
+    let determinant = edge_b.dot(&p);
+    if determinant.abs() < ZERO_TOL {
+        return None;
+    }
+
+    let inverse = 1.0 / determinant;
+    let offset = ray.origin - a;
+    let v = offset.dot(&p) * inverse;
+    if !(0.0..=1.0).contains(&v) {
+        return None;
+    }
+
+    let q = offset.cross(&edge_b);
+    let w = ray.direction.dot(&q) * inverse;
+    if w < 0.0 || v + w > 1.0 {
+        return None;
+    }
+
+    Some((edge_c.dot(&q) * inverse, v, w))
+}
+
+impl Geometry for Heightfield {
+    fn collide(&self, ray: &Ray, t_range: Range) -> Option<Collision> {
+        let (original_ray, ray) = (ray, self.transform * (*ray));
+        let Range(t_start, t_end) = self.local_bounds.check_intersection(&ray, t_range)?;
+
+        // 2D DDA over the cells in the xz plane, starting from where the ray enters the bounds
+        let Dimensions(width, depth) = self.dimensions;
+        let start = ray.at(t_start);
+        let mut cell = [
+            (start.x.floor().max(0.0) as usize).min(width - 2),
+            (start.z.floor().max(0.0) as usize).min(depth - 2),
+        ];
+
+        let mut step = [0isize; 2];
+        let mut t_next = [Float::INFINITY; 2];
+        let mut t_delta = [Float::INFINITY; 2];
+        for (index, axis) in [0, 2].into_iter().enumerate() {
+            let direction = ray.direction[axis];
+            if direction.abs() < ZERO_TOL {
+                continue;
+            }
+
+            let boundary = cell[index] as Float + if direction > 0.0 { 1.0 } else { 0.0 };
+            step[index] = direction.signum() as isize;
+            t_next[index] = (boundary - ray.origin[axis]) / direction;
+            t_delta[index] = 1.0 / direction.abs();
+        }
+
+        // Cells overlap slightly, so that hits right on their boundaries aren't missed
+        let tolerance = SPAN_TOL / ray.direction.norm();
+        let mut t_enter = t_start;
+        loop {
+            let t_exit = t_next[0].min(t_next[1]).min(t_end);
+            let Range(lowest, highest) = self.cell_ranges[cell[0] + (width - 1) * cell[1]];
+
+            // Only cells whose heights overlap those of the ray while crossing them can be hit
+            let (y_enter, y_exit) = (ray.at(t_enter).y, ray.at(t_exit).y);
+            if y_enter.max(y_exit) >= lowest && y_enter.min(y_exit) <= highest {
+                let cell_range = Range(
+                    t_range.0.max(t_enter - tolerance),
+                    t_range.1.min(t_exit + tolerance),
+                );
+
+                let cell = (cell[0], cell[1]);
+                if let Some(hit) = self.collide_cell(&ray, cell, cell_range) {
+                    return Some(self.make_collision(original_ray, &ray, cell, hit));
+                }
+            }
+
+            if t_exit >= t_end {
+                return None;
+            }
+
+            let index = if t_next[0] < t_next[1] { 0 } else { 1 };
+            let limit = [width - 2, depth - 2][index];
+            match cell[index].checked_add_signed(step[index]) {
+                Some(next) if next <= limit => cell[index] = next,
+                _ => return None,
+            }
+
+            t_enter = t_exit;
+            t_next[index] += t_delta[index];
+        }
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        self.bounds
+    }
+}
@@ -0,0 +1,59 @@
+use super::*;
+use std::sync::Arc;
+
+/// Places shared geometry in the world through a transform, so that many copies of the same object only
+/// store it once
+#[derive(Clone)]
+pub struct Instance {
+    object: WorldObject,
+    transform: Transform,
+    bounds: BoundingBox,
+}
+
+impl Instance {
+    /// `placement` maps the object's own coordinates to world coordinates
+    pub fn new(object: WorldObject, placement: Transform) -> Arc<Self> {
+        let bounds = object.bounding_box().transformed(placement);
+
+        Arc::new(Self {
+            object,
+            transform: placement.inverse(),
+            bounds,
+        })
+    }
+}
+
+impl Geometry for Instance {
+    fn collide(&self, ray: &Ray, t_range: Range) -> Option<Collision> {
+        // Directions aren't normalized, so t is the same in both spaces
+        let local_ray = self.transform * (*ray);
+
+        let mut collision = self.object.collide(&local_ray, t_range)?;
+        collision.untransform(self.transform);
+
+        Some(collision)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        self.bounds
+    }
+
+    fn sample_surface(&self, time: Float) -> Option<SurfaceSample> {
+        let sample = self.object.sample_surface(time)?;
+        let normal = Vector::from(self.transform / Normal::from(sample.normal));
+
+        Some(SurfaceSample {
+            point: self.transform / sample.point,
+            normal: normal.normalize(),
+            pdf: sample.pdf / self.transform.inverse().area_scale(sample.normal),
+        })
+    }
+
+    fn surface_pdf(&self, point: &Point, normal: &Vector, time: Float) -> Float {
+        let local_normal = Vector::from(self.transform * Normal::from(*normal)).normalize();
+
+        self.object
+            .surface_pdf(&(self.transform * *point), &local_normal, time)
+            / self.transform.inverse().area_scale(local_normal)
+    }
+}
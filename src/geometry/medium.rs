@@ -0,0 +1,216 @@
+use super::*;
+use density::Density;
+use material::Material;
+use std::sync::Arc;
+
+/// A volume of uniform density, such as fog or smoke, filling a closed boundary. Rays travelling through it
+/// collide at random distances, with the density being the probability of colliding per unit of length,
+/// and scatter according to the material, which is usually `material::volume::Isotropic`.
+#[derive(Clone)]
+pub struct ConstantMedium {
+    boundary: WorldObject,
+    density: Float,
+    phase_function: Arc<dyn Material>,
+}
+
+impl ConstantMedium {
+    /// Will error out if the density isn't positive
+    pub fn new(
+        boundary: WorldObject,
+        density: Float,
+        phase_function: Arc<dyn Material>,
+    ) -> Arc<Self> {
+        assert! { density > 0.0 }
+
+        Arc::new(Self {
+            boundary,
+            density,
+            phase_function,
+        })
+    }
+}
+
+impl Geometry for ConstantMedium {
+    fn collide(&self, ray: &Ray, t_range: Range) -> Option<Collision> {
+        let speed = ray.direction.norm();
+
+        // The boundary is searched past the end of the range, otherwise a range ending inside the medium
+        // would hide the exit that tells the ray started inside
+        let boundary_range = Range(t_range.0, Float::INFINITY);
+        for Span { entry, exit } in self.boundary.intervals(ray, boundary_range) {
+            let start = entry.map_or(t_range.0, |collision| collision.t);
+            let end = exit.map_or(t_range.1, |collision| collision.t.min(t_range.1));
+            if start >= t_range.1 {
+                break;
+            }
+
+            // Free-flight distances are exponentially distributed, and since that distribution is
+            // memoryless a fresh one can be sampled for every span
+            let distance = -(1.0 - random::random_float()).ln() / self.density;
+            let t = start + distance / speed;
+            if t >= end {
+                continue;
+            }
+
+            // Scattering doesn't depend on the normal, so any unit vector will do
+            let normal = -ray.direction / speed;
+            let (tangent, bitangent) = normal.orthonormal_basis();
+
+            return Some(Collision {
+                point: ray.at(t),
+                normal,
+                t,
+                uv: UV(0.0, 0.0),
+                tangent,
+                bitangent,
+                is_front_facing: true,
+                material: self.phase_function.clone(),
+            });
+        }
+
+        None
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        self.boundary.bounding_box()
+    }
+}
+
+/// A volume whose density varies in space, filling a closed boundary. Collisions are found through delta
+/// tracking: tentative collisions are sampled as if the whole volume had the majorant density, and each one
+/// is accepted with probability proportional to the actual density there.
+#[derive(Clone)]
+pub struct HeterogeneousMedium {
+    boundary: WorldObject,
+    density: Arc<dyn Density>,
+    phase_function: Arc<dyn Material>,
+}
+
+impl HeterogeneousMedium {
+    pub fn new(
+        boundary: WorldObject,
+        density: Arc<dyn Density>,
+        phase_function: Arc<dyn Material>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            boundary,
+            density,
+            phase_function,
+        })
+    }
+}
+
+impl Geometry for HeterogeneousMedium {
+    fn collide(&self, ray: &Ray, t_range: Range) -> Option<Collision> {
+        let majorant = self.density.majorant();
+        if majorant <= 0.0 {
+            return None;
+        }
+
+        let speed = ray.direction.norm();
+
+        // As with constant media, the boundary is searched past the end of the range
+        let boundary_range = Range(t_range.0, Float::INFINITY);
+        for Span { entry, exit } in self.boundary.intervals(ray, boundary_range) {
+            let mut t = entry.map_or(t_range.0, |collision| collision.t);
+            let end = exit.map_or(t_range.1, |collision| collision.t.min(t_range.1));
+            if t >= t_range.1 {
+                break;
+            }
+
+            loop {
+                t += -(1.0 - random::random_float()).ln() / majorant / speed;
+                if t >= end {
+                    break;
+                }
+
+                let point = ray.at(t);
+                if random::random_float() * majorant >= self.density.density(&point) {
+                    // A null collision, the ray carries on unchanged
+                    continue;
+                }
+
+                let normal = -ray.direction / speed;
+                let (tangent, bitangent) = normal.orthonormal_basis();
+
+                return Some(Collision {
+                    point,
+                    normal,
+                    t,
+                    uv: UV(0.0, 0.0),
+                    tangent,
+                    bitangent,
+                    is_front_facing: true,
+                    material: self.phase_function.clone(),
+                });
+            }
+        }
+
+        None
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        self.boundary.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cuboid::Cuboid;
+    use material::volume::Isotropic;
+
+    const TRIALS: usize = 1000;
+
+    /// Uniform density, which makes delta tracking behave like the constant medium
+    struct Uniform(Float);
+
+    impl Density for Uniform {
+        fn density(&self, _point: &Point) -> Float {
+            self.0
+        }
+
+        fn majorant(&self) -> Float {
+            self.0
+        }
+    }
+
+    fn fog() -> WorldObject {
+        let phase_function = Isotropic::new(Color::new(0.5, 0.5, 0.5));
+        Cuboid::new(
+            Point::new(-10.0, -10.0, -10.0),
+            Point::new(10.0, 10.0, 10.0),
+            phase_function,
+        )
+    }
+
+    /// How many rays from the center of the fog collide within the range
+    fn hits(medium: &dyn Geometry, t_range: Range) -> usize {
+        let ray = Ray::new(Point::new(0.0, 0.0, 0.0), Vector::new(1.0, 0.0, 0.0));
+        (0..TRIALS)
+            .filter_map(|_| medium.collide(&ray, t_range))
+            .inspect(|collision| assert!(t_range.contains(collision.t)))
+            .count()
+    }
+
+    /// Out of 1000 rays at a density of 1, about 993 collide within 5 units, and all but a handful before
+    /// leaving the fog
+    fn check_range_ending_inside(medium: &dyn Geometry) {
+        assert!(hits(medium, Range(0.001, Float::INFINITY)) > 990);
+        assert!(hits(medium, Range(0.001, 5.0)) > 950);
+        assert!(hits(medium, Range(0.001, 0.01)) < 50);
+    }
+
+    #[test]
+    fn constant_range_ending_inside() {
+        let phase_function = Isotropic::new(Color::new(0.5, 0.5, 0.5));
+        check_range_ending_inside(ConstantMedium::new(fog(), 1.0, phase_function).as_ref());
+    }
+
+    #[test]
+    fn heterogeneous_range_ending_inside() {
+        let phase_function = Isotropic::new(Color::new(0.5, 0.5, 0.5));
+        let medium = HeterogeneousMedium::new(fog(), Arc::new(Uniform(1.0)), phase_function);
+        check_range_ending_inside(medium.as_ref());
+    }
+}
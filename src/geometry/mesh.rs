@@ -0,0 +1,260 @@
+use super::*;
+use material::Material;
+use math::ZERO_TOL;
+use std::sync::Arc;
+
+/// A set of triangles sharing a single vertex and index buffer, with optional per-vertex normals and
+/// texture coordinates
+#[derive(Clone)]
+pub struct TriangleMesh {
+    vertices: Vec<Point>,
+    indices: Vec<[usize; 3]>,
+    normals: Option<Vec<Vector>>,
+    uvs: Option<Vec<UV>>,
+    material: Arc<dyn Material>,
+}
+
+impl TriangleMesh {
+    /// Builds a mesh from its buffers, where each face indexes three vertices in counter-clockwise order.
+    /// Will error out if an index is out of bounds, or if normals or uvs don't have one entry per vertex
+    pub fn new(
+        vertices: Vec<Point>,
+        indices: Vec<[usize; 3]>,
+        normals: Option<Vec<Vector>>,
+        uvs: Option<Vec<UV>>,
+        material: Arc<dyn Material>,
+    ) -> Arc<Self> {
+        assert! { indices.iter().flatten().all(|&index| index < vertices.len()) }
+        if let Some(ref normals) = normals {
+            assert_eq! { normals.len(), vertices.len() }
+        }
+        if let Some(ref uvs) = uvs {
+            assert_eq! { uvs.len(), vertices.len() }
+        }
+
+        Arc::new(Self {
+            vertices,
+            indices,
+            normals,
+            uvs,
+            material,
+        })
+    }
+
+    /// Returns the number of faces in the mesh
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    pub fn indices(&self) -> &[[usize; 3]] {
+        &self.indices
+    }
+
+    pub fn normals(&self) -> Option<&[Vector]> {
+        self.normals.as_deref()
+    }
+
+    pub fn uvs(&self) -> Option<&[UV]> {
+        self.uvs.as_deref()
+    }
+
+    /// Splits the mesh into one object per face, all of them sharing the mesh's buffers
+    pub fn triangles(self: &Arc<Self>) -> Vec<WorldObject> {
+        (0..self.len())
+            .map(|face| Triangle::from_mesh(self.clone(), face) as WorldObject)
+            .collect()
+    }
+
+    fn face_vertices(&self, face: usize) -> [Point; 3] {
+        let [a, b, c] = self.indices[face];
+        [self.vertices[a], self.vertices[b], self.vertices[c]]
+    }
+}
+
+/// A single face of a triangle mesh
+#[derive(Clone)]
+pub struct Triangle {
+    mesh: Arc<TriangleMesh>,
+    face: usize,
+    bounds: BoundingBox,
+}
+
+impl Triangle {
+    /// Builds a standalone triangle, backed by a mesh with a single face.
+    /// Vertices are expected in counter-clockwise order as seen from the front.
+    pub fn new(a: Point, b: Point, c: Point, material: Arc<dyn Material>) -> Arc<Self> {
+        let mesh = TriangleMesh::new(vec![a, b, c], vec![[0, 1, 2]], None, None, material);
+
+        Self::from_mesh(mesh, 0)
+    }
+
+    /// Builds the triangle corresponding to a face of the mesh.
+    /// Will error out if the face doesn't exist
+    pub fn from_mesh(mesh: Arc<TriangleMesh>, face: usize) -> Arc<Self> {
+        assert! { face < mesh.len() }
+
+        let [a, b, c] = mesh.face_vertices(face);
+        let bounds = BoundingBox::from_extrema(a, b).union(&BoundingBox::from_extrema(b, c));
+
+        Arc::new(Self { mesh, face, bounds })
+    }
+
+    /// Without texture coordinates, the barycentric coordinates parametrize the face
+    fn face_uvs(&self) -> [UV; 3] {
+        match self.mesh.uvs() {
+            Some(uvs) => {
+                let [i, j, k] = self.mesh.indices[self.face];
+                [uvs[i], uvs[j], uvs[k]]
+            }
+            None => [UV(0.0, 0.0), UV(1.0, 0.0), UV(0.0, 1.0)],
+        }
+    }
+
+    /// Solves for the directions in which u and v increase, projected onto the plane orthogonal to the
+    /// (possibly interpolated) normal. Degenerate texture coordinates get an arbitrary frame instead.
+    fn tangent_frame(vertices: [Point; 3], uvs: [UV; 3], normal: Vector) -> (Vector, Vector) {
+        let (edge_a, edge_b) = (vertices[1] - vertices[0], vertices[2] - vertices[0]);
+        let (du_a, dv_a) = (uvs[1].0 - uvs[0].0, uvs[1].1 - uvs[0].1);
+        let (du_b, dv_b) = (uvs[2].0 - uvs[0].0, uvs[2].1 - uvs[0].1);
+
+        let determinant = du_a * dv_b - du_b * dv_a;
+        if determinant.abs() < ZERO_TOL {
+            return normal.orthonormal_basis();
+        }
+
+        let dp_du = (dv_b * edge_a - dv_a * edge_b) / determinant;
+        let dp_dv = (du_a * edge_b - du_b * edge_a) / determinant;
+
+        let project = |vector: Vector| (vector - vector.dot(&normal) * normal).normalize();
+        (project(dp_du), project(dp_dv))
+    }
+
+    /// Returns the index of the component of largest magnitude
+    fn max_dimension(vector: Vector) -> usize {
+        let abs = vector.data.abs();
+        if abs.x > abs.y {
+            if abs.x > abs.z {
+                0
+            } else {
+                2
+            }
+        } else if abs.y > abs.z {
+            1
+        } else {
+            2
+        }
+    }
+}
+
+impl Geometry for Triangle {
+    /// Watertight ray/triangle intersection as described by Woop, Benthin and Wald (2013).
+    /// Rays hitting a shared edge or vertex will always collide with at least one of the adjacent faces.
+    fn collide(&self, ray: &Ray, t_range: Range) -> Option<Collision> {
+        let [p0, p1, p2] = self.mesh.face_vertices(self.face);
+
+        // Permuting the axes so that z is the dominant direction of the ray, while preserving winding
+        let kz = Self::max_dimension(ray.direction);
+        let mut kx = (kz + 1) % 3;
+        let mut ky = (kx + 1) % 3;
+        if ray.direction[kz] < 0.0 {
+            std::mem::swap(&mut kx, &mut ky);
+        }
+
+        // Shearing so that the ray direction becomes (0, 0, 1)
+        let shear_x = ray.direction[kx] / ray.direction[kz];
+        let shear_y = ray.direction[ky] / ray.direction[kz];
+        let shear_z = 1.0 / ray.direction[kz];
+
+        let a = p0 - ray.origin;
+        let b = p1 - ray.origin;
+        let c = p2 - ray.origin;
+
+        let (ax, ay) = (a[kx] - shear_x * a[kz], a[ky] - shear_y * a[kz]);
+        let (bx, by) = (b[kx] - shear_x * b[kz], b[ky] - shear_y * b[kz]);
+        let (cx, cy) = (c[kx] - shear_x * c[kz], c[ky] - shear_y * c[kz]);
+
+        let mut u = cx * by - cy * bx;
+        let mut v = ax * cy - ay * cx;
+        let mut w = bx * ay - by * ax;
+
+        // Edge cases are recomputed in double precision so that neighbouring faces agree on them
+        if u == 0.0 || v == 0.0 || w == 0.0 {
+            u = (cx as f64 * by as f64 - cy as f64 * bx as f64) as Float;
+            v = (ax as f64 * cy as f64 - ay as f64 * cx as f64) as Float;
+            w = (bx as f64 * ay as f64 - by as f64 * ax as f64) as Float;
+        }
+
+        if (u < 0.0 || v < 0.0 || w < 0.0) && (u > 0.0 || v > 0.0 || w > 0.0) {
+            return None;
+        }
+
+        let determinant = u + v + w;
+        if determinant == 0.0 {
+            return None;
+        }
+
+        let scaled_t = u * shear_z * a[kz] + v * shear_z * b[kz] + w * shear_z * c[kz];
+        let t = scaled_t / determinant;
+        if t_range.not_contains(t) {
+            return None;
+        }
+
+        let barycentric = [u / determinant, v / determinant, w / determinant];
+        let point = Point::from(
+            barycentric[0] * Vector::from(p0)
+                + barycentric[1] * Vector::from(p1)
+                + barycentric[2] * Vector::from(p2),
+        );
+
+        let geometric_normal = (p1 - p0).cross(&(p2 - p0)).normalize();
+        let (is_front_facing, geometric_normal) = get_face(ray, geometric_normal);
+
+        let normal = match self.mesh.normals() {
+            Some(normals) => {
+                let [i, j, k] = self.mesh.indices[self.face];
+                let shading_normal = (barycentric[0] * normals[i]
+                    + barycentric[1] * normals[j]
+                    + barycentric[2] * normals[k])
+                    .normalize();
+
+                // Shading normals must agree with the side of the surface that was hit
+                if shading_normal.dot(&geometric_normal) < 0.0 {
+                    -shading_normal
+                } else {
+                    shading_normal
+                }
+            }
+            None => geometric_normal,
+        };
+
+        let uvs = self.face_uvs();
+        let uv = UV(
+            barycentric[0] * uvs[0].0 + barycentric[1] * uvs[1].0 + barycentric[2] * uvs[2].0,
+            barycentric[0] * uvs[0].1 + barycentric[1] * uvs[1].1 + barycentric[2] * uvs[2].1,
+        );
+        let (tangent, bitangent) = Self::tangent_frame([p0, p1, p2], uvs, normal);
+
+        Some(Collision {
+            point,
+            normal,
+            t,
+            uv,
+            tangent,
+            bitangent,
+            is_front_facing,
+            material: self.mesh.material.clone(),
+        })
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        self.bounds
+    }
+}
@@ -1,33 +1,132 @@
 use super::*;
 use bounding::BoundingBox;
+use math::ZERO_TOL;
 use render::Ray;
 use transform::Transform;
 
+pub mod cone;
+pub mod csg;
+pub mod cuboid;
+pub mod cylinder;
+pub mod density;
 pub mod flat;
+pub mod heightfield;
+pub mod instance;
+pub mod medium;
+pub mod mesh;
+pub mod moving;
+pub mod quadric;
+pub mod sdf;
 pub mod sphere;
+pub mod torus;
 
 #[derive(Clone)]
 pub struct Collision {
     pub point: Point,
     pub normal: Vector,
     pub t: Float,
+    /// Surface parameters at the collision point
+    pub uv: UV,
+    /// Unit vector tangent to the surface, along which u increases
+    pub tangent: Vector,
+    /// Unit vector tangent to the surface, along which v increases. It isn't necessarily orthogonal to
+    /// the tangent.
+    pub bitangent: Vector,
     pub is_front_facing: bool,
     pub material: std::sync::Arc<dyn material::Material>,
 }
 
 impl Collision {
+    /// Maps a collision found in the local space of `transform` back to world space, taking the
+    /// normal found in local space as the outward normal
     pub fn apply(&mut self, ray: &Ray, transform: Transform) {
-        self.point = transform / self.point;
-        let (is_front_facing, normal) = get_face(ray, transform * self.normal);
+        self.untransform(transform);
+        let (is_front_facing, normal) = get_face(ray, self.normal);
 
         self.is_front_facing = is_front_facing;
-        self.normal = normal.normalize();
+        self.normal = normal;
+    }
+
+    /// Maps a collision found in the local space of `transform` back to world space, keeping the facing
+    /// that was already determined
+    pub fn untransform(&mut self, transform: Transform) {
+        self.point = transform / self.point;
+        self.normal = Vector::from(transform / Normal::from(self.normal)).normalize();
+
+        // Tangents are differences of points on the surface, so they map back like any other vector
+        self.tangent = (transform / self.tangent).normalize();
+        self.bitangent = (transform / self.bitangent).normalize();
     }
 }
 
+/// A point sampled on the surface of some geometry
+#[derive(Debug, Clone, Copy)]
+pub struct SurfaceSample {
+    pub point: Point,
+    pub normal: Vector,
+    /// Probability density of the point, with respect to surface area
+    pub pdf: Float,
+}
+
+/// How far past a collision the search for the next one starts, when walking along a ray. It's a distance,
+/// so it has to be divided by the speed of the ray to get an offset in t.
+const SPAN_TOL: Float = 1e-4;
+
+/// A stretch of a ray that's inside some solid, bounded by the collisions at which the ray enters and exits
+/// it. Either end is None when the stretch extends beyond the range that was searched.
+#[derive(Clone)]
+pub struct Span {
+    pub entry: Option<Collision>,
+    pub exit: Option<Collision>,
+}
+
 pub trait Geometry: std::marker::Send + std::marker::Sync {
     fn collide(&self, ray: &Ray, t_range: Range) -> Option<Collision>;
     fn bounding_box(&self) -> BoundingBox;
+
+    /// Samples a point on the surface as it is at `time`, which is how light sources are sampled directly.
+    /// Geometry that doesn't support it returns None, which is the default.
+    fn sample_surface(&self, _time: Float) -> Option<SurfaceSample> {
+        None
+    }
+
+    /// Probability density, with respect to surface area, of `sample_surface` returning a given point at
+    /// `time`. Assumes the point lies on the surface, and that `normal` is the unit normal there.
+    fn surface_pdf(&self, _point: &Point, _normal: &Vector, _time: Float) -> Float {
+        0.0
+    }
+
+    /// Returns, in order, every span of the ray inside the geometry within the range. This only makes sense
+    /// for closed surfaces, and by default it's found by walking through the collisions along the ray,
+    /// telling entries from exits by their facing.
+    fn intervals(&self, ray: &Ray, t_range: Range) -> Vec<Span> {
+        let mut spans = vec![];
+        let mut entry = None;
+        let mut is_first = true;
+
+        let tolerance = SPAN_TOL / ray.direction.norm();
+        let mut start = t_range.0;
+        while let Some(collision) = self.collide(ray, Range(start, t_range.1)) {
+            start = collision.t + tolerance;
+
+            if collision.is_front_facing {
+                entry.get_or_insert(collision);
+            } else if entry.is_some() || is_first {
+                // Exiting first means the ray started inside
+                spans.push(Span {
+                    entry: entry.take(),
+                    exit: Some(collision),
+                });
+            }
+            is_first = false;
+        }
+
+        if entry.is_some() {
+            spans.push(Span { entry, exit: None });
+        }
+
+        spans
+    }
 }
 
 impl Geometry for &[WorldObject] {
@@ -93,3 +192,33 @@ fn get_face(ray: &Ray, outward_normal: Vector) -> (bool, Vector) {
         (true, outward_normal)
     }
 }
+
+/// Given a point, returns its polar coordinates around the z axis, with the angle normalized to [0, 1],
+/// along with the directions in which they increase
+fn polar_coordinates(local: Point) -> (UV, Vector, Vector) {
+    let radius = local.x.hypot(local.y);
+    let phi = local.y.atan2(local.x).rem_euclid(2.0 * math::PI);
+    let uv = UV(phi / (2.0 * math::PI), radius);
+
+    // The angle isn't defined at the center, where any tangent frame will do
+    if radius < ZERO_TOL {
+        return (uv, Vector::x(), Vector::y());
+    }
+
+    let tangent = Vector::new(-local.y, local.x, 0.0) / radius;
+    let bitangent = Vector::new(local.x, local.y, 0.0) / radius;
+
+    (uv, tangent, bitangent)
+}
+
+/// Returns the t at which a ray crosses the unit disk orthogonal to z and centered at (0, 0, height)
+fn collide_unit_disk(ray: &Ray, height: Float) -> Option<Float> {
+    if ray.direction.z.abs() <= ZERO_TOL {
+        return None;
+    }
+
+    let t = (height - ray.origin.z) / ray.direction.z;
+    let point = ray.at(t);
+
+    (point.x * point.x + point.y * point.y <= 1.0).then_some(t)
+}
@@ -0,0 +1,108 @@
+use super::*;
+use std::sync::Arc;
+use transform::AnimatedTransform;
+
+/// How many instants between consecutive keyframes are checked when bounding the motion
+const BOUND_STEPS: usize = 64;
+
+/// Places geometry in the world through a transform that changes over time, so that it's blurred along
+/// its motion while the camera's shutter is open
+#[derive(Clone)]
+pub struct Moving {
+    object: WorldObject,
+    animation: AnimatedTransform,
+    bounds: BoundingBox,
+}
+
+impl Moving {
+    /// Each keyframe pairs a time with the placement of the object at that time, which maps the object's
+    /// own coordinates to world coordinates. In between, placements are interpolated by splitting them
+    /// into translation, rotation and stretch.
+    /// Will error out if there are no keyframes, or if only some of the placements are mirrored
+    pub fn new(object: WorldObject, keyframes: Vec<(Float, Transform)>) -> Arc<Self> {
+        let animation = AnimatedTransform::new(keyframes);
+        let bounds = Self::motion_bounds(&object.bounding_box(), &animation);
+
+        Arc::new(Self {
+            object,
+            animation,
+            bounds,
+        })
+    }
+
+    /// Moves the object in a straight line, from `start` at time 0 to `end` at time 1
+    pub fn linear(object: WorldObject, start: Vector, end: Vector) -> Arc<Self> {
+        Self::new(
+            object,
+            vec![
+                (0.0, Transform::translate(start)),
+                (1.0, Transform::translate(end)),
+            ],
+        )
+    }
+
+    /// Bounds the object over the whole animation, by placing it at many instants in between the keyframes
+    fn motion_bounds(bounds: &BoundingBox, animation: &AnimatedTransform) -> BoundingBox {
+        let times = animation.times();
+        let mut instants = vec![times[0]];
+        for pair in times.windows(2) {
+            let (start, end) = (pair[0], pair[1]);
+            instants.extend(
+                (1..=BOUND_STEPS)
+                    .map(|step| start + (end - start) * step as Float / BOUND_STEPS as Float),
+            );
+        }
+
+        let swept = instants
+            .into_iter()
+            .map(|time| bounds.transformed(animation.at(time)))
+            .reduce(|acc, elem| acc.union(&elem))
+            .expect("There is at least one keyframe");
+
+        // Rotating corners sweep arcs that bulge slightly past the instants that were checked
+        let [x, y, z] = swept.axes();
+        let padding = 1e-3 * (x.length() + y.length() + z.length());
+        let pad = |Range(min, max): Range| Range(min - padding, max + padding);
+
+        BoundingBox::new(pad(x), pad(y), pad(z))
+    }
+}
+
+impl Geometry for Moving {
+    fn collide(&self, ray: &Ray, t_range: Range) -> Option<Collision> {
+        let transform = self.animation.at(ray.time).inverse();
+
+        // Directions aren't normalized, so t is the same in both spaces
+        let local_ray = transform * (*ray);
+
+        let mut collision = self.object.collide(&local_ray, t_range)?;
+        collision.untransform(transform);
+
+        Some(collision)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        self.bounds
+    }
+
+    fn sample_surface(&self, time: Float) -> Option<SurfaceSample> {
+        let placement = self.animation.at(time);
+        let sample = self.object.sample_surface(time)?;
+        let normal = Vector::from(placement * Normal::from(sample.normal));
+
+        Some(SurfaceSample {
+            point: placement * sample.point,
+            normal: normal.normalize(),
+            pdf: sample.pdf / placement.area_scale(sample.normal),
+        })
+    }
+
+    fn surface_pdf(&self, point: &Point, normal: &Vector, time: Float) -> Float {
+        let placement = self.animation.at(time);
+        let local_normal = Vector::from(placement / Normal::from(*normal)).normalize();
+
+        self.object
+            .surface_pdf(&(placement / *point), &local_normal, time)
+            / placement.area_scale(local_normal)
+    }
+}
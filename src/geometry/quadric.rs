@@ -0,0 +1,104 @@
+use super::*;
+use material::Material;
+use nalgebra::{Matrix4, Vector4};
+use polynomial::solve_quadratic;
+use std::sync::Arc;
+
+/// The implicit surface pᵀQp = 0, where p = (x, y, z, 1) and Q is a symmetric 4x4 matrix, clipped to a box
+/// in local space. For instance, diag(1, 1, 0, 0) plus -1 at (2, 3) and (3, 2) gives the paraboloid
+/// x² + y² = 2z, while diag(1, 1, -1, -1) gives a hyperboloid of one sheet.
+#[derive(Clone)]
+pub struct Quadric {
+    transform: Transform,
+    material: Arc<dyn Material>,
+    coefficients: Matrix4<Float>,
+    clip: BoundingBox,
+    bounds: BoundingBox,
+}
+
+impl Quadric {
+    /// Builds a quadric given the rows of its matrix, the box it's clipped to and a transform placing it in
+    /// the world, i.e.: mapping local coordinates to world coordinates. Points where pᵀQp is positive are
+    /// outside.
+    /// Will error out if the matrix isn't symmetric
+    pub fn new(
+        rows: [[Float; 4]; 4],
+        clip: BoundingBox,
+        placement: Transform,
+        material: Arc<dyn Material>,
+    ) -> Arc<Self> {
+        let coefficients = Matrix4::from_fn(|row, column| rows[row][column]);
+        assert! { coefficients.relative_eq(&coefficients.transpose(), ZERO_TOL, 1e-6) }
+
+        Arc::new(Self {
+            transform: placement.inverse(),
+            material,
+            coefficients,
+            clip,
+            bounds: clip.transformed(placement),
+        })
+    }
+
+    fn is_clipped(&self, point: Point) -> bool {
+        let [x, y, z] = self.clip.axes();
+        x.not_contains(point.x) || y.not_contains(point.y) || z.not_contains(point.z)
+    }
+}
+
+impl Geometry for Quadric {
+    fn collide(&self, ray: &Ray, t_range: Range) -> Option<Collision> {
+        let (original_ray, ray) = (ray, self.transform * (*ray));
+
+        let origin: Vector4<Float> = ray.origin.unified();
+        let direction: Vector4<Float> = ray.direction.unified();
+        let q_origin = self.coefficients * origin;
+
+        let a = direction.dot(&(self.coefficients * direction));
+        let b = 2.0 * direction.dot(&q_origin);
+        let c = origin.dot(&q_origin);
+
+        let t = solve_quadratic(a as f64, b as f64, c as f64)
+            .into_iter()
+            .map(|root| root as Float)
+            .find(|&t| t_range.contains(t) && !self.is_clipped(ray.at(t)))?;
+
+        let glancing_point = ray.at(t);
+        let normal = Vector {
+            data: (self.coefficients * glancing_point.unified()).xyz(),
+        };
+        if normal.norm_squared() < ZERO_TOL {
+            return None;
+        }
+        let normal = normal.normalize();
+
+        // Parametrized around the z axis of the clipping box, like a surface of revolution
+        let (UV(u, _), tangent, _) = polar_coordinates(glancing_point);
+        let Range(z_min, z_max) = self.clip.axes()[2];
+        let uv = UV(u, (glancing_point.z - z_min) / (z_max - z_min));
+
+        let tangent = tangent - tangent.dot(&normal) * normal;
+        let tangent = if tangent.norm_squared() < ZERO_TOL {
+            normal.orthonormal_basis().0
+        } else {
+            tangent.normalize()
+        };
+
+        let mut collision = Collision {
+            point: glancing_point,
+            normal,
+            t,
+            uv,
+            tangent,
+            bitangent: normal.cross(&tangent),
+            is_front_facing: true,
+            material: self.material.clone(),
+        };
+        collision.apply(original_ray, self.transform);
+
+        Some(collision)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        self.bounds
+    }
+}
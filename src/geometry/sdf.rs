@@ -0,0 +1,314 @@
+use super::*;
+use material::Material;
+use std::sync::Arc;
+
+const MAX_STEPS: usize = 512;
+/// Distance to the surface under which the ray is considered to have hit it
+const HIT_TOL: Float = 1e-4;
+
+/// A tree of signed distance functions, negative inside the shape and positive outside of it. Primitives are
+/// centered at the origin, and revolve around the z axis where that applies.
+#[derive(Debug, Clone)]
+pub enum SdfNode {
+    Sphere {
+        radius: Float,
+    },
+    Cuboid {
+        half_extents: Vector,
+    },
+    Torus {
+        major_radius: Float,
+        minor_radius: Float,
+    },
+    Translate {
+        offset: Vector,
+        node: Box<SdfNode>,
+    },
+    Union(Box<SdfNode>, Box<SdfNode>),
+    Intersection(Box<SdfNode>, Box<SdfNode>),
+    /// Everything in the first node that isn't in the second one
+    Subtraction(Box<SdfNode>, Box<SdfNode>),
+    /// Union that blends the surfaces where they're closer than the smoothness
+    SmoothUnion {
+        left: Box<SdfNode>,
+        right: Box<SdfNode>,
+        smoothness: Float,
+    },
+    /// Subtraction that blends the surfaces where they're closer than the smoothness
+    SmoothSubtraction {
+        left: Box<SdfNode>,
+        right: Box<SdfNode>,
+        smoothness: Float,
+    },
+    /// Tiles space with copies of the node, with the given period along each axis. Axes with a period of 0
+    /// aren't repeated.
+    Repeat {
+        period: Vector,
+        node: Box<SdfNode>,
+    },
+    /// Rotates the node around the z axis by an angle proportional to z, in degrees per unit of length
+    Twist {
+        rate: Float,
+        node: Box<SdfNode>,
+    },
+}
+
+impl SdfNode {
+    pub fn sphere(radius: Float) -> Self {
+        Self::Sphere { radius }
+    }
+
+    pub fn cuboid(half_extents: Vector) -> Self {
+        Self::Cuboid { half_extents }
+    }
+
+    pub fn torus(major_radius: Float, minor_radius: Float) -> Self {
+        Self::Torus {
+            major_radius,
+            minor_radius,
+        }
+    }
+
+    pub fn translate(self, offset: Vector) -> Self {
+        Self::Translate {
+            offset,
+            node: Box::new(self),
+        }
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self::Union(Box::new(self), Box::new(other))
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        Self::Intersection(Box::new(self), Box::new(other))
+    }
+
+    pub fn subtract(self, other: Self) -> Self {
+        Self::Subtraction(Box::new(self), Box::new(other))
+    }
+
+    /// Will error out if the smoothness isn't positive
+    pub fn smooth_union(self, other: Self, smoothness: Float) -> Self {
+        assert! { smoothness > 0.0 }
+
+        Self::SmoothUnion {
+            left: Box::new(self),
+            right: Box::new(other),
+            smoothness,
+        }
+    }
+
+    /// Will error out if the smoothness isn't positive
+    pub fn smooth_subtract(self, other: Self, smoothness: Float) -> Self {
+        assert! { smoothness > 0.0 }
+
+        Self::SmoothSubtraction {
+            left: Box::new(self),
+            right: Box::new(other),
+            smoothness,
+        }
+    }
+
+    /// Will error out if any period is negative
+    pub fn repeat(self, period: Vector) -> Self {
+        assert! { period.x >= 0.0 && period.y >= 0.0 && period.z >= 0.0 }
+
+        Self::Repeat {
+            period,
+            node: Box::new(self),
+        }
+    }
+
+    pub fn twist(self, rate: Float) -> Self {
+        Self::Twist {
+            rate,
+            node: Box::new(self),
+        }
+    }
+
+    /// Signed distance from the point to the surface, or a lower bound of it for nodes that distort space
+    pub fn distance(&self, point: &Point) -> Float {
+        match self {
+            SdfNode::Sphere { radius } => Vector::from(*point).norm() - radius,
+            SdfNode::Cuboid { half_extents } => {
+                let q = Vector {
+                    data: point.data.abs() - half_extents.data,
+                };
+                let outside = Vector {
+                    data: q.data.map(|x| x.max(0.0)),
+                };
+
+                outside.norm() + q.x.max(q.y).max(q.z).min(0.0)
+            }
+            SdfNode::Torus {
+                major_radius,
+                minor_radius,
+            } => {
+                let ring = point.x.hypot(point.y) - major_radius;
+                ring.hypot(point.z) - minor_radius
+            }
+            SdfNode::Translate { offset, node } => node.distance(&(*point - *offset)),
+            SdfNode::Union(left, right) => left.distance(point).min(right.distance(point)),
+            SdfNode::Intersection(left, right) => left.distance(point).max(right.distance(point)),
+            SdfNode::Subtraction(left, right) => left.distance(point).max(-right.distance(point)),
+            SdfNode::SmoothUnion {
+                left,
+                right,
+                smoothness,
+            } => {
+                let (a, b) = (left.distance(point), right.distance(point));
+                let h = (0.5 + 0.5 * (b - a) / smoothness).clamp(0.0, 1.0);
+
+                b + (a - b) * h - smoothness * h * (1.0 - h)
+            }
+            SdfNode::SmoothSubtraction {
+                left,
+                right,
+                smoothness,
+            } => {
+                let (a, b) = (left.distance(point), -right.distance(point));
+                let h = (0.5 - 0.5 * (a - b) / smoothness).clamp(0.0, 1.0);
+
+                a + (b - a) * h + smoothness * h * (1.0 - h)
+            }
+            SdfNode::Repeat { period, node } => {
+                let mut local = *point;
+                for axis in 0..3 {
+                    if period[axis] > 0.0 {
+                        local[axis] -= period[axis] * (local[axis] / period[axis]).round();
+                    }
+                }
+
+                node.distance(&local)
+            }
+            SdfNode::Twist { rate, node } => {
+                let angle = math::degrees_to_radians(*rate) * point.z;
+                let (sin, cos) = angle.sin_cos();
+                let local = Point::new(
+                    cos * point.x - sin * point.y,
+                    sin * point.x + cos * point.y,
+                    point.z,
+                );
+
+                node.distance(&local)
+            }
+        }
+    }
+
+    /// Bound on how fast the distance can change, for points within `extent` of the origin. It's 1 for exact
+    /// distance functions, but twisting stretches space and makes it larger.
+    fn lipschitz(&self, extent: Float) -> Float {
+        match self {
+            SdfNode::Sphere { .. } | SdfNode::Cuboid { .. } | SdfNode::Torus { .. } => 1.0,
+            SdfNode::Translate { node, .. } | SdfNode::Repeat { node, .. } => {
+                node.lipschitz(extent)
+            }
+            SdfNode::Union(left, right)
+            | SdfNode::Intersection(left, right)
+            | SdfNode::Subtraction(left, right)
+            | SdfNode::SmoothUnion { left, right, .. }
+            | SdfNode::SmoothSubtraction { left, right, .. } => {
+                left.lipschitz(extent).max(right.lipschitz(extent))
+            }
+            SdfNode::Twist { rate, node } => {
+                let stretch = math::degrees_to_radians(*rate) * extent;
+                node.lipschitz(extent) * stretch.hypot(1.0)
+            }
+        }
+    }
+}
+
+/// A shape given by a signed distance function, intersected by sphere tracing. Since distance functions may
+/// be unbounded, the user has to provide a box enclosing the surface, outside of which it's ignored.
+/// There's no natural parametrization, so uv is always (0, 0) and solid textures work best.
+#[derive(Clone)]
+pub struct Sdf {
+    root: SdfNode,
+    bounds: BoundingBox,
+    lipschitz: Float,
+    material: Arc<dyn Material>,
+}
+
+impl Sdf {
+    pub fn new(root: SdfNode, bounds: BoundingBox, material: Arc<dyn Material>) -> Arc<Self> {
+        let extent = bounds
+            .corners()
+            .iter()
+            .map(|&corner| Vector::from(corner).norm())
+            .fold(0.0, Float::max);
+
+        Arc::new(Self {
+            lipschitz: root.lipschitz(extent),
+            root,
+            bounds,
+            material,
+        })
+    }
+
+    /// Gradient of the distance function through central differences, sampled on a tetrahedron
+    fn gradient(&self, point: &Point) -> Vector {
+        const STEP: Float = 1e-4;
+        let offsets = [
+            Vector::new(1.0, -1.0, -1.0),
+            Vector::new(-1.0, -1.0, 1.0),
+            Vector::new(-1.0, 1.0, -1.0),
+            Vector::new(1.0, 1.0, 1.0),
+        ];
+
+        offsets.iter().fold(Vector::zeros(), |acc, &offset| {
+            acc + self.root.distance(&(*point + STEP * offset)) * offset
+        })
+    }
+}
+
+impl Geometry for Sdf {
+    fn collide(&self, ray: &Ray, t_range: Range) -> Option<Collision> {
+        let Range(mut t, t_max) = self.bounds.check_intersection(ray, t_range)?;
+        let speed = ray.direction.norm();
+
+        // Rays leaving the surface would otherwise hit it again right away
+        if self.root.distance(&ray.at(t)).abs() < HIT_TOL {
+            t += 2.0 * HIT_TOL / speed;
+        }
+
+        // Rays starting inside the shape march towards where they exit it
+        let side = self.root.distance(&ray.at(t)).signum();
+
+        for _ in 0..MAX_STEPS {
+            let distance = side * self.root.distance(&ray.at(t));
+            if distance < HIT_TOL {
+                let point = ray.at(t);
+                let gradient = self.gradient(&point);
+                if gradient.norm_squared() < ZERO_TOL * ZERO_TOL {
+                    return None;
+                }
+
+                let (is_front_facing, normal) = get_face(ray, gradient.normalize());
+                let (tangent, bitangent) = normal.orthonormal_basis();
+
+                return Some(Collision {
+                    point,
+                    normal,
+                    t,
+                    uv: UV(0.0, 0.0),
+                    tangent,
+                    bitangent,
+                    is_front_facing,
+                    material: self.material.clone(),
+                });
+            }
+
+            t += distance / (self.lipschitz * speed);
+            if t > t_max {
+                return None;
+            }
+        }
+
+        None
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        self.bounds
+    }
+}
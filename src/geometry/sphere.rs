@@ -1,6 +1,7 @@
 use super::*;
 use bounding::BoundingBox;
 use material::Material;
+use math::ZERO_TOL;
 use std::sync::Arc;
 use transform::Transform;
 
@@ -9,6 +10,7 @@ pub struct Ellipsoid {
     transform: Transform,
     material: Arc<dyn Material>,
     bounds: BoundingBox,
+    semiaxes: Vector,
 }
 
 impl Ellipsoid {
@@ -22,6 +24,7 @@ impl Ellipsoid {
             transform,
             material,
             bounds: BoundingBox::from_extrema(center - radius_vec, center + radius_vec),
+            semiaxes: radius_vec,
         })
     }
 
@@ -35,8 +38,45 @@ impl Ellipsoid {
             transform,
             material,
             bounds: BoundingBox::from_extrema(center - semiaxes, center + semiaxes),
+            semiaxes,
         })
     }
+
+    /// Given a point on the unit sphere, returns its longitude and latitude, normalized to [0, 1] and
+    /// starting from -x and -y respectively, along with the directions in which they increase
+    fn spherical_coordinates(local: Vector) -> (UV, Vector, Vector) {
+        let theta = (-local.y).clamp(-1.0, 1.0).acos();
+        let phi = (-local.z).atan2(local.x) + math::PI;
+        let uv = UV(phi / (2.0 * math::PI), theta / math::PI);
+
+        // The partial derivatives vanish at the poles, where any tangent frame will do
+        let radius = local.x.hypot(local.z);
+        if radius < ZERO_TOL {
+            let (tangent, bitangent) = local.orthonormal_basis();
+            return (uv, tangent, bitangent);
+        }
+
+        let tangent = Vector::new(local.z, 0.0, -local.x) / radius;
+        let bitangent =
+            Vector::new(-local.x * local.y, radius * radius, -local.z * local.y) / radius;
+
+        (uv, tangent, bitangent.normalize())
+    }
+
+    /// Given a point on the unit sphere, returns the normal at the corresponding point of the ellipsoid.
+    /// The result isn't normalized.
+    fn stretched_normal(&self, local: Vector) -> Vector {
+        (self.transform / Normal::from(local)).into()
+    }
+
+    /// Points are sampled uniformly on the unit sphere and then stretched into the ellipsoid, so the
+    /// density is inversely proportional to how much the area around each point was stretched
+    fn local_pdf(&self, local: Vector) -> Float {
+        let Vector { data } = self.semiaxes;
+        let area_scale = data.product().abs() * self.stretched_normal(local).norm();
+
+        1.0 / (4.0 * math::PI * area_scale)
+    }
 }
 
 impl Geometry for Ellipsoid {
@@ -63,14 +103,18 @@ impl Geometry for Ellipsoid {
         }
 
         let glancing_point = ray.at(root);
+        let (uv, tangent, bitangent) = Self::spherical_coordinates(glancing_point.into());
         let mut collision = Collision {
             point: glancing_point,
             normal: glancing_point.into(),
             t: root,
+            uv,
+            tangent,
+            bitangent,
             is_front_facing: true,
             material: self.material.clone(),
         };
-        collision.apply(&original_ray, self.transform);
+        collision.apply(original_ray, self.transform);
 
         Some(collision)
     }
@@ -78,4 +122,18 @@ impl Geometry for Ellipsoid {
     fn bounding_box(&self) -> BoundingBox {
         self.bounds
     }
+
+    fn sample_surface(&self, _time: Float) -> Option<SurfaceSample> {
+        let local = random::random_unit_vector();
+
+        Some(SurfaceSample {
+            point: self.transform / Point::from(local),
+            normal: self.stretched_normal(local).normalize(),
+            pdf: self.local_pdf(local),
+        })
+    }
+
+    fn surface_pdf(&self, point: &Point, _normal: &Vector, _time: Float) -> Float {
+        self.local_pdf((self.transform * *point).into())
+    }
 }
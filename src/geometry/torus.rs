@@ -0,0 +1,119 @@
+use super::*;
+use material::Material;
+use polynomial::solve_quartic;
+use std::sync::Arc;
+
+/// A ring torus, which in local space is centered at the origin and revolves around the z axis
+#[derive(Clone)]
+pub struct Torus {
+    transform: Transform,
+    material: Arc<dyn Material>,
+    bounds: BoundingBox,
+    local_bounds: BoundingBox,
+    major_radius: Float,
+    minor_radius: Float,
+}
+
+impl Torus {
+    /// Builds a torus given its center, the axis it revolves around, the distance from the center to
+    /// the middle of the tube and the radius of the tube.
+    /// Will error out if the axis is the zero vector or either radius isn't positive
+    pub fn new(
+        center: Point,
+        axis: Vector,
+        major_radius: Float,
+        minor_radius: Float,
+        material: Arc<dyn Material>,
+    ) -> Arc<Self> {
+        assert! { axis.norm_squared() > ZERO_TOL }
+        assert! { major_radius > 0.0 && minor_radius > 0.0 }
+
+        let axis = axis.normalize();
+        let (u, v) = axis.orthonormal_basis();
+        let transform = Transform::make_basis(u, v, axis) * Transform::make_origin(center);
+
+        let extent = major_radius + minor_radius;
+        let local_bounds = BoundingBox::new(
+            Range(-extent, extent),
+            Range(-extent, extent),
+            Range(-minor_radius, minor_radius),
+        );
+
+        Arc::new(Self {
+            transform,
+            material,
+            bounds: local_bounds.transformed(transform.inverse()),
+            local_bounds,
+            major_radius,
+            minor_radius,
+        })
+    }
+}
+
+impl Geometry for Torus {
+    fn collide(&self, ray: &Ray, t_range: Range) -> Option<Collision> {
+        let (original_ray, ray) = (ray, self.transform * (*ray));
+
+        // The quartic is much better conditioned when the origin is close to the torus, so it's moved
+        // to where the ray enters the bounds
+        let Range(t_near, _) = self.local_bounds.check_intersection(&ray, t_range)?;
+        let origin = ray.at(t_near);
+        let direction = ray.direction;
+
+        let (ox, oy, oz) = (origin.x as f64, origin.y as f64, origin.z as f64);
+        let (dx, dy, dz) = (direction.x as f64, direction.y as f64, direction.z as f64);
+        let major_sq = (self.major_radius as f64).powi(2);
+        let minor_sq = (self.minor_radius as f64).powi(2);
+
+        // Expanding (|p|² + R² - r²)² = 4R²(x² + y²) along the ray
+        let dd = dx * dx + dy * dy + dz * dz;
+        let od = ox * dx + oy * dy + oz * dz;
+        let k = ox * ox + oy * oy + oz * oz + major_sq - minor_sq;
+        let roots = solve_quartic(
+            dd * dd,
+            4.0 * dd * od,
+            4.0 * od * od + 2.0 * dd * k - 4.0 * major_sq * (dx * dx + dy * dy),
+            4.0 * od * k - 8.0 * major_sq * (ox * dx + oy * dy),
+            k * k - 4.0 * major_sq * (ox * ox + oy * oy),
+        );
+
+        let t = roots
+            .into_iter()
+            .map(|root| t_near + root as Float)
+            .find(|&t| t_range.contains(t))?;
+
+        let glancing_point = ray.at(t);
+        let Point { data } = glancing_point;
+        let radius = data.x.hypot(data.y).max(ZERO_TOL);
+        let ring = Vector::new(data.x, data.y, 0.0) / radius;
+
+        // The normal points away from the closest point in the middle of the tube
+        let normal = Vector::from(glancing_point) - self.major_radius * ring;
+        let phi = data.y.atan2(data.x).rem_euclid(2.0 * math::PI);
+        let psi = data
+            .z
+            .atan2(radius - self.major_radius)
+            .rem_euclid(2.0 * math::PI);
+
+        let tangent = Vector::new(-ring.y, ring.x, 0.0);
+        let bitangent = Vector::z() * psi.cos() - ring * psi.sin();
+
+        let mut collision = Collision {
+            point: glancing_point,
+            normal,
+            t,
+            uv: UV(phi / (2.0 * math::PI), psi / (2.0 * math::PI)),
+            tangent,
+            bitangent,
+            is_front_facing: true,
+            material: self.material.clone(),
+        };
+        collision.apply(original_ray, self.transform);
+
+        Some(collision)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        self.bounds
+    }
+}
@@ -0,0 +1,225 @@
+use super::*;
+use image::Image;
+use std::path::Path;
+
+/// Loads a Radiance RGBE (.hdr) image, with either flat or run-length encoded scanlines
+pub fn load(path: impl AsRef<Path>) -> Result<Image, LoadError> {
+    let path = path.as_ref();
+    let bytes = read_file(path)?;
+
+    decode(&bytes).map_err(|message| LoadError::format(path, message))
+}
+
+/// Decodes the contents of a Radiance RGBE file
+pub fn decode(bytes: &[u8]) -> Result<Image, String> {
+    let mut cursor = 0;
+
+    let magic = next_line(bytes, &mut cursor)?;
+    if !magic.starts_with("#?") {
+        return Err("missing the #? signature, this is not a Radiance file".to_string());
+    }
+
+    // The header is a list of variables terminated by an empty line
+    loop {
+        let line = next_line(bytes, &mut cursor)?;
+        if line.is_empty() {
+            break;
+        }
+
+        if let Some(format) = line.strip_prefix("FORMAT=") {
+            if format != "32-bit_rle_rgbe" {
+                return Err(format!("unsupported pixel format `{format}`"));
+            }
+        }
+    }
+
+    let resolution = next_line(bytes, &mut cursor)?;
+    let (flip_vertical, dimensions) = parse_resolution(&resolution)?;
+    let Dimensions(width, height) = dimensions;
+
+    // The resolution can't be trusted until the file is known to be large enough to hold it
+    width
+        .checked_mul(height)
+        .ok_or_else(|| format!("resolution `{resolution}` is too large"))?;
+    let needed = min_scanline_size(width).and_then(|size| size.checked_mul(height));
+    if needed.is_none_or(|needed| needed > bytes.len() - cursor) {
+        return Err(format!(
+            "resolution `{resolution}` needs more pixel data than the file holds"
+        ));
+    }
+
+    let mut pixels = vec![];
+    let mut scanline = vec![[0u8; 4]; width];
+    for _ in 0..height {
+        read_scanline(bytes, &mut cursor, &mut scanline)?;
+        pixels.extend(scanline.iter().map(|rgbe| rgbe_to_color(*rgbe)));
+    }
+
+    if flip_vertical {
+        let rows: Vec<_> = pixels.chunks(width).rev().flatten().copied().collect();
+        pixels = rows;
+    }
+
+    Ok(Image::new(dimensions, pixels))
+}
+
+fn next_line(bytes: &[u8], cursor: &mut usize) -> Result<String, String> {
+    let rest = &bytes[*cursor..];
+    let end = rest
+        .iter()
+        .position(|&byte| byte == b'\n')
+        .ok_or("unexpected end of file in the header")?;
+
+    *cursor += end + 1;
+    Ok(String::from_utf8_lossy(&rest[..end]).trim().to_string())
+}
+
+/// Parses the resolution string, returning whether rows are stored bottom to top and the dimensions.
+/// Only the standard orientations, where rows are along x, are supported.
+fn parse_resolution(line: &str) -> Result<(bool, Dimensions), String> {
+    let tokens: Vec<_> = line.split_whitespace().collect();
+    let error = || format!("unsupported resolution string `{line}`");
+
+    let [y_sign, height, x_sign, width] = tokens[..] else {
+        return Err(error());
+    };
+
+    let flip_vertical = match (y_sign, x_sign) {
+        ("-Y", "+X") => false,
+        ("+Y", "+X") => true,
+        _ => return Err(error()),
+    };
+
+    let height: usize = height.parse().map_err(|_| error())?;
+    let width: usize = width.parse().map_err(|_| error())?;
+    if width == 0 || height == 0 {
+        return Err(format!("empty image with resolution `{line}`"));
+    }
+
+    Ok((flip_vertical, Dimensions(width, height)))
+}
+
+/// Fewest bytes a scanline can take. New style run-length encoding packs at most 127 pixels of a channel
+/// into two bytes, after a 4 byte header, and flat scanlines take 4 bytes per pixel. Files packed any
+/// tighter through old style runs are treated as corrupt.
+fn min_scanline_size(width: usize) -> Option<usize> {
+    if (8..0x8000).contains(&width) {
+        Some(4 + 8 * width.div_ceil(127))
+    } else {
+        width.checked_mul(4)
+    }
+}
+
+fn read_byte(bytes: &[u8], cursor: &mut usize) -> Result<u8, String> {
+    let byte = *bytes
+        .get(*cursor)
+        .ok_or("unexpected end of file in the pixel data")?;
+    *cursor += 1;
+
+    Ok(byte)
+}
+
+fn read_scanline(bytes: &[u8], cursor: &mut usize, scanline: &mut [[u8; 4]]) -> Result<(), String> {
+    let width = scanline.len();
+    let start = *cursor;
+
+    let mut header = [0; 4];
+    for byte in header.iter_mut() {
+        *byte = read_byte(bytes, cursor)?;
+    }
+
+    // New style run-length encoding stores each channel separately, and is flagged by two 2s
+    let is_run_length_encoded =
+        (8..0x8000).contains(&width) && header[0] == 2 && header[1] == 2 && header[2] & 0x80 == 0;
+
+    if !is_run_length_encoded {
+        *cursor = start;
+        return read_flat_scanline(bytes, cursor, scanline);
+    }
+
+    let encoded_width = ((header[2] as usize) << 8) | header[3] as usize;
+    if encoded_width != width {
+        return Err(format!(
+            "scanline has width {encoded_width}, but the image has width {width}"
+        ));
+    }
+
+    for channel in 0..4 {
+        let mut x = 0;
+        while x < width {
+            let count = read_byte(bytes, cursor)? as usize;
+
+            if count > 128 {
+                let run = count - 128;
+                let value = read_byte(bytes, cursor)?;
+                if x + run > width {
+                    return Err("run overflows the scanline".to_string());
+                }
+
+                for pixel in scanline[x..x + run].iter_mut() {
+                    pixel[channel] = value;
+                }
+                x += run;
+            } else {
+                if count == 0 || x + count > width {
+                    return Err("invalid run length in scanline".to_string());
+                }
+
+                for pixel in scanline[x..x + count].iter_mut() {
+                    pixel[channel] = read_byte(bytes, cursor)?;
+                }
+                x += count;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads uncompressed pixels, also handling the old style of run-length encoding where a pixel of
+/// (1, 1, 1, n) repeats the previous pixel
+fn read_flat_scanline(
+    bytes: &[u8],
+    cursor: &mut usize,
+    scanline: &mut [[u8; 4]],
+) -> Result<(), String> {
+    let mut x = 0;
+    let mut shift = 0;
+
+    while x < scanline.len() {
+        let mut pixel = [0; 4];
+        for byte in pixel.iter_mut() {
+            *byte = read_byte(bytes, cursor)?;
+        }
+
+        if pixel[..3] == [1, 1, 1] && x > 0 {
+            let run = (pixel[3] as usize) << shift;
+            if x + run > scanline.len() {
+                return Err("run overflows the scanline".to_string());
+            }
+
+            let previous = scanline[x - 1];
+            scanline[x..x + run].fill(previous);
+            x += run;
+            shift += 8;
+        } else {
+            scanline[x] = pixel;
+            x += 1;
+            shift = 0;
+        }
+    }
+
+    Ok(())
+}
+
+fn rgbe_to_color(rgbe: [u8; 4]) -> Color {
+    let [r, g, b, exponent] = rgbe;
+    if exponent == 0 {
+        return Color::default();
+    }
+
+    // The mantissas are 8 bit fractions, hence the extra 8 in the exponent
+    let scale = (2.0 as Float).powi(exponent as i32 - (128 + 8));
+
+    Color::new(r as Float, g as Float, b as Float) * scale
+}
@@ -0,0 +1,91 @@
+use super::*;
+use std::path::Path;
+
+/// A linear, floating point RGB image laid out row by row, starting from the top left corner
+#[derive(Debug, Clone)]
+pub struct Image {
+    dimensions: Dimensions,
+    pixels: Vec<Color>,
+}
+
+impl Image {
+    /// Will error out if the number of pixels doesn't match the dimensions
+    pub fn new(dimensions: Dimensions, pixels: Vec<Color>) -> Self {
+        let Dimensions(width, height) = dimensions;
+        assert_eq! { pixels.len(), width * height }
+
+        Self { dimensions, pixels }
+    }
+
+    pub fn dimensions(&self) -> Dimensions {
+        self.dimensions
+    }
+
+    /// Will panic if the pixel is out of bounds
+    #[inline]
+    pub fn get(&self, x: usize, y: usize) -> Color {
+        let Dimensions(width, _) = self.dimensions;
+        self.pixels[x + width * y]
+    }
+
+    /// Applies a function to every pixel
+    pub fn map<F>(self, f: F) -> Self
+    where
+        F: Fn(Color) -> Color,
+    {
+        Self {
+            dimensions: self.dimensions,
+            pixels: self.pixels.into_iter().map(f).collect(),
+        }
+    }
+}
+
+/// Loads an 8 or 16 bit PNG file, with its values normalized to [0, 1] but otherwise untouched, so no
+/// gamma decoding takes place. Grayscale images have the same value in every channel, and alpha is dropped.
+pub fn load_png(path: impl AsRef<Path>) -> Result<Image, LoadError> {
+    let path = path.as_ref();
+    let file = std::fs::File::open(path).map_err(|source| LoadError::io(path, source))?;
+    let decoding_error = |error: png::DecodingError| LoadError::format(path, error.to_string());
+
+    // Expanding turns palettes into RGB and makes every depth at least 8 bits
+    let mut decoder = png::Decoder::new(std::io::BufReader::new(file));
+    decoder.set_transformations(png::Transformations::EXPAND);
+
+    let mut reader = decoder.read_info().map_err(decoding_error)?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).map_err(decoding_error)?;
+
+    let (color_type, bit_depth) = reader.output_color_type();
+    let channels = color_type.samples();
+    let (bytes_per_sample, max_value) = match bit_depth {
+        png::BitDepth::Eight => (1, u8::MAX as Float),
+        png::BitDepth::Sixteen => (2, u16::MAX as Float),
+        _ => return Err(LoadError::format(path, "unsupported bit depth")),
+    };
+
+    let sample = |bytes: &[u8]| -> Float {
+        let value = match bytes_per_sample {
+            1 => bytes[0] as Float,
+            _ => u16::from_be_bytes([bytes[0], bytes[1]]) as Float,
+        };
+
+        value / max_value
+    };
+
+    let pixels = buffer[..info.buffer_size()]
+        .chunks_exact(channels * bytes_per_sample)
+        .map(|pixel| {
+            let values: Vec<_> = pixel.chunks_exact(bytes_per_sample).map(sample).collect();
+
+            match color_type {
+                png::ColorType::Grayscale | png::ColorType::GrayscaleAlpha => {
+                    Color::new(values[0], values[0], values[0])
+                }
+                _ => Color::new(values[0], values[1], values[2]),
+            }
+        })
+        .collect();
+
+    let dimensions = Dimensions(info.width as usize, info.height as usize);
+    Ok(Image::new(dimensions, pixels))
+}
@@ -1,5 +1,83 @@
 use super::*;
 
+pub mod hdr;
+pub mod image;
+pub mod obj;
+pub mod volume;
+
+use std::path::{Path, PathBuf};
+
+/// Errors that may come up while loading images, meshes, materials and volumes from disk
+#[derive(Debug)]
+pub enum LoadError {
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The file was read but its contents are invalid. Text formats also tell the offending line.
+    Format {
+        path: PathBuf,
+        line: Option<usize>,
+        message: String,
+    },
+}
+
+impl LoadError {
+    pub(crate) fn io(path: &Path, source: std::io::Error) -> Self {
+        Self::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+
+    pub(crate) fn format(path: &Path, message: impl Into<String>) -> Self {
+        Self::Format {
+            path: path.to_path_buf(),
+            line: None,
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn format_at(path: &Path, line: usize, message: impl Into<String>) -> Self {
+        Self::Format {
+            path: path.to_path_buf(),
+            line: Some(line),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            LoadError::Format {
+                path,
+                line: Some(line),
+                message,
+            } => write!(f, "{}:{line}: {message}", path.display()),
+            LoadError::Format {
+                path,
+                line: None,
+                message,
+            } => write!(f, "{}: {message}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Io { source, .. } => Some(source),
+            LoadError::Format { .. } => None,
+        }
+    }
+}
+
+pub(crate) fn read_file(path: &Path) -> Result<Vec<u8>, LoadError> {
+    std::fs::read(path).map_err(|source| LoadError::io(path, source))
+}
+
 #[derive(Debug, Clone)]
 pub struct PngTile {
     dimensions: Dimensions,
@@ -0,0 +1,602 @@
+use super::*;
+use geometry::mesh::TriangleMesh;
+use material::{
+    dielectric::Dielectric, diffuse::Lambertian, emissive::DiffuseLight, metal::Metal, Material,
+};
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::Path;
+use std::sync::Arc;
+
+type Materials = HashMap<String, Arc<dyn Material>>;
+
+/// Loads an OBJ file, along with any MTL files it references, as a list of triangles.
+/// MTL files are looked up relative to the directory of the OBJ file. Missing MTL files and undefined
+/// materials only print a warning, and the faces using them get a default gray material.
+pub fn load(path: impl AsRef<Path>) -> Result<World, LoadError> {
+    let path = path.as_ref();
+    let reader = open(path)?;
+    let directory = path.parent().unwrap_or(Path::new(""));
+
+    parse(reader, &path.display().to_string(), directory)
+}
+
+/// Parses OBJ data from a reader. `name` is only used for error messages, and `directory` is where
+/// referenced MTL files are looked up.
+pub fn parse<R: BufRead>(reader: R, name: &str, directory: &Path) -> Result<World, LoadError> {
+    let mut parser = ObjParser::new(name);
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.map_err(|source| LoadError::io(Path::new(name), source))?;
+
+        parser.line = index + 1;
+        parser.parse_line(&line, directory)?;
+    }
+
+    Ok(parser.finish())
+}
+
+/// Loads an MTL file, mapping each material onto the closest material the engine supports
+pub fn load_materials(path: impl AsRef<Path>) -> Result<Materials, LoadError> {
+    let path = path.as_ref();
+    let reader = open(path)?;
+
+    parse_materials(reader, &path.display().to_string())
+}
+
+/// Parses MTL data from a reader. `name` is only used for error messages.
+///
+/// Materials are mapped as follows:
+/// - materials with an emissive color `Ke` become `DiffuseLight`s
+/// - transparent materials (`d` < 1, `Tr` > 0 or a refractive `illum` model) become `Dielectric`s with
+///   index of refraction `Ni`
+/// - materials with a specular color and either no diffuse color or a reflective `illum` model become
+///   `Metal`s, whose fuzziness shrinks as the specular exponent `Ns` grows
+/// - everything else becomes a `Lambertian` with the diffuse color `Kd`
+pub fn parse_materials<R: BufRead>(reader: R, name: &str) -> Result<Materials, LoadError> {
+    let mut materials = HashMap::new();
+    let mut current: Option<(String, MtlParameters)> = None;
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.map_err(|source| LoadError::io(Path::new(name), source))?;
+        let mut tokens = Tokens::new(&line, name, index + 1);
+
+        let keyword = match tokens.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+
+        if keyword == "newmtl" {
+            if let Some((name, parameters)) = current.take() {
+                materials.insert(name, parameters.to_material());
+            }
+
+            current = Some((tokens.rest()?, MtlParameters::default()));
+            continue;
+        }
+
+        let parameters = match current {
+            Some((_, ref mut parameters)) => parameters,
+            None => match keyword {
+                "Kd" | "Ks" | "Ke" | "Ns" | "Ni" | "d" | "Tr" | "illum" => {
+                    return Err(tokens.error(format!("`{keyword}` found before any `newmtl`")))
+                }
+                _ => continue,
+            },
+        };
+
+        match keyword {
+            "Kd" => parameters.diffuse = tokens.color()?,
+            "Ks" => parameters.specular = tokens.color()?,
+            "Ke" => parameters.emission = tokens.color()?,
+            "Ns" => parameters.exponent = tokens.float()?,
+            "Ni" => parameters.refraction_index = tokens.float()?,
+            "d" => parameters.dissolve = tokens.float()?,
+            "Tr" => parameters.dissolve = 1.0 - tokens.float()?,
+            "illum" => parameters.illumination = tokens.integer()?,
+            // Texture maps and other statements aren't supported yet
+            _ => {}
+        }
+    }
+
+    if let Some((name, parameters)) = current {
+        materials.insert(name, parameters.to_material());
+    }
+
+    Ok(materials)
+}
+
+fn open(path: &Path) -> Result<std::io::BufReader<std::fs::File>, LoadError> {
+    let file = std::fs::File::open(path).map_err(|source| LoadError::io(path, source))?;
+
+    Ok(std::io::BufReader::new(file))
+}
+
+#[derive(Debug, Clone, Copy)]
+struct MtlParameters {
+    diffuse: Color,
+    specular: Color,
+    emission: Color,
+    exponent: Float,
+    refraction_index: Float,
+    dissolve: Float,
+    illumination: usize,
+}
+
+impl Default for MtlParameters {
+    fn default() -> Self {
+        Self {
+            diffuse: Color::new(0.8, 0.8, 0.8),
+            specular: Color::default(),
+            emission: Color::default(),
+            exponent: 0.0,
+            refraction_index: 1.5,
+            dissolve: 1.0,
+            illumination: 1,
+        }
+    }
+}
+
+impl MtlParameters {
+    fn to_material(self) -> Arc<dyn Material> {
+        let is_black = |color: Color| color.data.max() <= 0.0;
+        let is_transparent = self.dissolve < 1.0 || matches!(self.illumination, 4 | 6 | 7 | 9);
+        let is_reflective = !is_black(self.specular)
+            && (is_black(self.diffuse) || matches!(self.illumination, 3 | 5 | 8));
+
+        if !is_black(self.emission) {
+            DiffuseLight::new(self.emission)
+        } else if is_transparent {
+            Dielectric::new(self.refraction_index)
+        } else if is_reflective {
+            // Maps the Phong exponent in [0, 1000] to a fuzziness in roughly [1, 0.05]
+            let fuzziness = (2.0 / (self.exponent.max(0.0) + 2.0)).sqrt();
+            Metal::new(self.specular, fuzziness)
+        } else {
+            Lambertian::new(self.diffuse)
+        }
+    }
+}
+
+/// Indices into the position, texture coordinate and normal lists of a single face corner
+type Corner = (usize, Option<usize>, Option<usize>);
+
+/// Faces that share the same group and material, which end up as a single mesh
+struct Batch {
+    material: Arc<dyn Material>,
+    faces: Vec<[Corner; 3]>,
+}
+
+struct ObjParser<'a> {
+    name: &'a str,
+    line: usize,
+    positions: Vec<Point>,
+    uvs: Vec<UV>,
+    normals: Vec<Vector>,
+    materials: Materials,
+    default_material: Arc<dyn Material>,
+    current_material: Arc<dyn Material>,
+    batches: Vec<Batch>,
+}
+
+impl<'a> ObjParser<'a> {
+    fn new(name: &'a str) -> Self {
+        let default_material: Arc<dyn Material> = Lambertian::new(Color::new(0.8, 0.8, 0.8));
+
+        Self {
+            name,
+            line: 0,
+            positions: Vec::new(),
+            uvs: Vec::new(),
+            normals: Vec::new(),
+            materials: HashMap::new(),
+            default_material: default_material.clone(),
+            current_material: default_material.clone(),
+            batches: vec![Batch {
+                material: default_material,
+                faces: Vec::new(),
+            }],
+        }
+    }
+
+    fn parse_line(&mut self, line: &str, directory: &Path) -> Result<(), LoadError> {
+        let mut tokens = Tokens::new(line, self.name, self.line);
+
+        let keyword = match tokens.next() {
+            Some(keyword) => keyword,
+            None => return Ok(()),
+        };
+
+        match keyword {
+            "v" => {
+                let position = tokens.point()?;
+                self.positions.push(position);
+            }
+            "vt" => {
+                let u = tokens.float()?;
+                let v = tokens.optional_float()?.unwrap_or(0.0);
+                self.uvs.push(UV(u, v));
+            }
+            "vn" => {
+                let normal = tokens.point()?;
+                self.normals.push(normal.into());
+            }
+            "f" => self.parse_face(&mut tokens)?,
+            "g" | "o" => self.start_batch(),
+            "usemtl" => {
+                let name = tokens.rest()?;
+                // Exporters often leave out materials, so the default one stands in for them. It's
+                // registered under the name so that the warning only shows up once.
+                self.current_material = match self.materials.get(&name) {
+                    Some(material) => material.clone(),
+                    None => {
+                        self.warn(&format!(
+                            "undefined material `{name}`, using the default one"
+                        ));
+                        self.materials.insert(name, self.default_material.clone());
+                        self.default_material.clone()
+                    }
+                };
+                self.start_batch();
+            }
+            "mtllib" => {
+                for file in tokens.by_ref() {
+                    match load_materials(directory.join(file)) {
+                        Ok(materials) => self.materials.extend(materials),
+                        // Missing libraries only leave their materials undefined
+                        Err(error @ LoadError::Io { .. }) => self.warn(&error.to_string()),
+                        Err(error) => return Err(error),
+                    }
+                }
+            }
+            // Smoothing groups, lines, points and free-form geometry are ignored
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn parse_face(&mut self, tokens: &mut Tokens) -> Result<(), LoadError> {
+        let mut corners = Vec::new();
+        for token in tokens.by_ref() {
+            corners.push(self.parse_corner(token)?);
+        }
+
+        if corners.len() < 3 {
+            return Err(tokens.error(format!(
+                "faces need at least 3 vertices, found {}",
+                corners.len()
+            )));
+        }
+
+        // N-gons are assumed to be convex and get fan triangulated around their first vertex
+        let batch = self.batches.last_mut().expect("There is always a batch");
+        for i in 1..corners.len() - 1 {
+            batch.faces.push([corners[0], corners[i], corners[i + 1]]);
+        }
+
+        Ok(())
+    }
+
+    fn parse_corner(&self, token: &str) -> Result<Corner, LoadError> {
+        let mut parts = token.split('/');
+
+        let position = parts.next().unwrap_or("");
+        let position = self.resolve_index(position, self.positions.len(), "vertex")?;
+
+        let uv = match parts.next() {
+            None | Some("") => None,
+            Some(index) => Some(self.resolve_index(index, self.uvs.len(), "texture coordinate")?),
+        };
+
+        let normal = match parts.next() {
+            None | Some("") => None,
+            Some(index) => Some(self.resolve_index(index, self.normals.len(), "normal")?),
+        };
+
+        if parts.next().is_some() {
+            return Err(self.error(format!("malformed face vertex `{token}`")));
+        }
+
+        Ok((position, uv, normal))
+    }
+
+    /// Converts a 1-based, possibly negative (i.e.: relative to the end) OBJ index to a 0-based one
+    fn resolve_index(&self, token: &str, length: usize, kind: &str) -> Result<usize, LoadError> {
+        let index: i64 = token
+            .parse()
+            .map_err(|_| self.error(format!("expected a {kind} index, found `{token}`")))?;
+
+        let resolved = if index < 0 {
+            length as i64 + index
+        } else {
+            index - 1
+        };
+
+        if index == 0 || resolved < 0 || resolved >= length as i64 {
+            return Err(self.error(format!(
+                "{kind} index {index} is out of range ({length} defined so far)"
+            )));
+        }
+
+        Ok(resolved as usize)
+    }
+
+    fn start_batch(&mut self) {
+        let batch = self.batches.last_mut().expect("There is always a batch");
+
+        if batch.faces.is_empty() {
+            batch.material = self.current_material.clone();
+        } else {
+            self.batches.push(Batch {
+                material: self.current_material.clone(),
+                faces: Vec::new(),
+            });
+        }
+    }
+
+    fn error(&self, message: String) -> LoadError {
+        LoadError::format_at(Path::new(self.name), self.line, message)
+    }
+
+    fn warn(&self, message: &str) {
+        eprintln!("{}:{}: warning: {message}", self.name, self.line);
+    }
+
+    /// Turns every batch into a mesh, deduplicating corners so that each mesh has its own compact buffers
+    fn finish(self) -> World {
+        let mut world = World::new();
+
+        for batch in self.batches.into_iter().filter(|b| !b.faces.is_empty()) {
+            let corners = batch.faces.iter().flatten();
+            let has_uvs = corners.clone().all(|(_, uv, _)| uv.is_some());
+            let has_normals = corners.clone().all(|(_, _, normal)| normal.is_some());
+
+            let mut remap = HashMap::new();
+            let mut vertices = Vec::new();
+            let mut uvs = Vec::new();
+            let mut normals = Vec::new();
+            let mut indices = Vec::new();
+
+            for face in batch.faces.iter() {
+                let mut triangle = [0; 3];
+
+                for (slot, &(position, uv, normal)) in triangle.iter_mut().zip(face.iter()) {
+                    // Attributes that won't end up in the mesh shouldn't split vertices
+                    let uv = uv.filter(|_| has_uvs);
+                    let normal = normal.filter(|_| has_normals);
+
+                    *slot = *remap.entry((position, uv, normal)).or_insert_with(|| {
+                        vertices.push(self.positions[position]);
+                        if let Some(uv) = uv {
+                            uvs.push(self.uvs[uv]);
+                        }
+                        if let Some(normal) = normal {
+                            normals.push(self.normals[normal]);
+                        }
+
+                        vertices.len() - 1
+                    });
+                }
+
+                indices.push(triangle);
+            }
+
+            let mesh = TriangleMesh::new(
+                vertices,
+                indices,
+                has_normals.then_some(normals),
+                has_uvs.then_some(uvs),
+                batch.material,
+            );
+            world.extend(mesh.triangles());
+        }
+
+        world
+    }
+}
+
+/// Whitespace separated tokens of a single line, ignoring comments
+struct Tokens<'a> {
+    inner: std::str::SplitWhitespace<'a>,
+    file: &'a str,
+    line: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(line: &'a str, file: &'a str, number: usize) -> Self {
+        let content = line.split('#').next().unwrap_or("");
+
+        Self {
+            inner: content.split_whitespace(),
+            file,
+            line: number,
+        }
+    }
+
+    fn error(&self, message: String) -> LoadError {
+        LoadError::format_at(Path::new(self.file), self.line, message)
+    }
+
+    fn float(&mut self) -> Result<Float, LoadError> {
+        match self.optional_float()? {
+            Some(value) => Ok(value),
+            None => Err(self.error("expected a number, found end of line".to_string())),
+        }
+    }
+
+    fn optional_float(&mut self) -> Result<Option<Float>, LoadError> {
+        match self.inner.next() {
+            Some(token) => token
+                .parse()
+                .map(Some)
+                .map_err(|_| self.error(format!("expected a number, found `{token}`"))),
+            None => Ok(None),
+        }
+    }
+
+    fn integer(&mut self) -> Result<usize, LoadError> {
+        match self.inner.next() {
+            Some(token) => token
+                .parse()
+                .map_err(|_| self.error(format!("expected an integer, found `{token}`"))),
+            None => Err(self.error("expected an integer, found end of line".to_string())),
+        }
+    }
+
+    fn point(&mut self) -> Result<Point, LoadError> {
+        Ok(Point::new(self.float()?, self.float()?, self.float()?))
+    }
+
+    fn color(&mut self) -> Result<Color, LoadError> {
+        Ok(Color::new(self.float()?, self.float()?, self.float()?))
+    }
+
+    /// Returns the remainder of the line, which is how names with spaces are handled
+    fn rest(&mut self) -> Result<String, LoadError> {
+        let rest = self.inner.by_ref().collect::<Vec<_>>().join(" ");
+        if rest.is_empty() {
+            Err(self.error("expected a name, found end of line".to_string()))
+        } else {
+            Ok(rest)
+        }
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use render::Ray;
+
+    fn parse_str(source: &str) -> Result<World, LoadError> {
+        parse(source.as_bytes(), "test.obj", Path::new(""))
+    }
+
+    /// Runs the parser over the source, without turning its batches into meshes
+    fn run_parser(source: &str) -> ObjParser<'static> {
+        let mut parser = ObjParser::new("test.obj");
+        for (index, line) in source.lines().enumerate() {
+            parser.line = index + 1;
+            parser.parse_line(line, Path::new("")).unwrap();
+        }
+
+        parser
+    }
+
+    /// Runs the parser over the source, returning the faces of every batch
+    fn faces(source: &str) -> Vec<Vec<[Corner; 3]>> {
+        run_parser(source)
+            .batches
+            .into_iter()
+            .map(|batch| batch.faces)
+            .collect()
+    }
+
+    const SQUARE: &str = "
+        v 0 0 0
+        v 1 0 0
+        v 1 1 0
+        v 0 1 0
+        vt 0 0
+        vt 1 0
+        vt 1 1
+        vt 0 1
+        vn 0 0 1
+    ";
+
+    #[test]
+    fn index_forms() {
+        let source =
+            format!("{SQUARE}\nf 1 2 3\nf 1/1 2/2 3/3\nf 1//1 2//1 3//1\nf 1/1/1 2/2/1 3/3/1");
+        let faces = faces(&source);
+
+        assert_eq!(
+            faces[0],
+            vec![
+                [(0, None, None), (1, None, None), (2, None, None)],
+                [(0, Some(0), None), (1, Some(1), None), (2, Some(2), None)],
+                [(0, None, Some(0)), (1, None, Some(0)), (2, None, Some(0))],
+                [
+                    (0, Some(0), Some(0)),
+                    (1, Some(1), Some(0)),
+                    (2, Some(2), Some(0))
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn vertex_attributes_reach_the_mesh() {
+        let world = parse_str(&format!("{SQUARE}\nf 1/1/1 2/2/1 3/3/1")).unwrap();
+        assert_eq!(world.len(), 1);
+
+        let ray = Ray::new(Point::new(0.75, 0.25, 1.0), Vector::new(0.0, 0.0, -1.0));
+        let collision = world[0]
+            .collide(&ray, Range(0.001, Float::INFINITY))
+            .unwrap();
+
+        assert!((collision.uv.0 - 0.75).abs() < 1e-5);
+        assert!((collision.uv.1 - 0.25).abs() < 1e-5);
+        assert!((collision.normal - Vector::new(0.0, 0.0, 1.0)).norm() < 1e-5);
+    }
+
+    #[test]
+    fn negative_indices() {
+        let positive = faces(&format!("{SQUARE}\nf 2/2/1 3/3/1 4/4/1"));
+        let negative = faces(&format!("{SQUARE}\nf -3/-3/-1 -2/-2/-1 -1/-1/-1"));
+
+        assert_eq!(positive, negative);
+    }
+
+    #[test]
+    fn polygons_are_fan_triangulated() {
+        let faces = faces(&format!("{SQUARE}\nv 0.5 1.5 0\nf 1 2 3 5 4"));
+        let positions: Vec<_> = faces[0]
+            .iter()
+            .map(|face| face.map(|(position, _, _)| position))
+            .collect();
+
+        assert_eq!(positions, vec![[0, 1, 2], [0, 2, 4], [0, 4, 3]]);
+        assert_eq!(parse_str(&format!("{SQUARE}\nf 1 2 3 4")).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn out_of_range_indices() {
+        assert!(parse_str(&format!("{SQUARE}\nf 1 2 5")).is_err());
+        assert!(parse_str(&format!("{SQUARE}\nf 0 1 2")).is_err());
+        assert!(parse_str(&format!("{SQUARE}\nf -5 1 2")).is_err());
+    }
+
+    #[test]
+    fn undefined_material() {
+        let source = format!("{SQUARE}\nusemtl missing\nf 1 2 3\nusemtl missing\nf 1 3 4");
+        assert_eq!(parse_str(&source).unwrap().len(), 2);
+
+        // Both batches fall back to the very same default material
+        let parser = run_parser(&source);
+        let used: Vec<_> = parser
+            .batches
+            .iter()
+            .filter(|batch| !batch.faces.is_empty())
+            .map(|batch| &batch.material)
+            .collect();
+        assert_eq!(used.len(), 2);
+        assert!(used
+            .iter()
+            .all(|material| Arc::ptr_eq(material, &parser.default_material)));
+    }
+
+    #[test]
+    fn missing_material_library() {
+        let source = format!("mtllib does_not_exist.mtl\n{SQUARE}\nusemtl red\nf 1 2 3");
+        assert_eq!(parse_str(&source).unwrap().len(), 1);
+    }
+}
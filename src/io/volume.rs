@@ -0,0 +1,96 @@
+use super::*;
+use std::path::Path;
+
+/// A 3D grid of scalar values, laid out with x varying fastest and z slowest
+#[derive(Debug, Clone)]
+pub struct Grid {
+    dimensions: [usize; 3],
+    values: Vec<Float>,
+}
+
+impl Grid {
+    /// Will error out if the number of values doesn't match the dimensions
+    pub fn new(dimensions: [usize; 3], values: Vec<Float>) -> Self {
+        assert_eq! { values.len(), dimensions.iter().product::<usize>() }
+
+        Self { dimensions, values }
+    }
+
+    pub fn dimensions(&self) -> [usize; 3] {
+        self.dimensions
+    }
+
+    pub fn values(&self) -> &[Float] {
+        &self.values
+    }
+
+    /// Will panic if the voxel is out of bounds
+    #[inline]
+    pub fn get(&self, x: usize, y: usize, z: usize) -> Float {
+        let [width, height, _] = self.dimensions;
+        self.values[x + width * (y + height * z)]
+    }
+}
+
+/// Loads a raw density grid: three little endian u32 with its dimensions, followed by one little endian
+/// f32 per voxel, all of which must be finite and not negative
+pub fn load(path: impl AsRef<Path>) -> Result<Grid, LoadError> {
+    let path = path.as_ref();
+    let bytes = read_file(path)?;
+
+    decode(&bytes).map_err(|message| LoadError::format(path, message))
+}
+
+/// Decodes the contents of a raw density grid
+pub fn decode(bytes: &[u8]) -> Result<Grid, String> {
+    const HEADER_SIZE: usize = 12;
+    if bytes.len() < HEADER_SIZE {
+        return Err("file is too short to hold the grid dimensions".to_string());
+    }
+
+    let word = |index: usize| {
+        let start = 4 * index;
+        [
+            bytes[start],
+            bytes[start + 1],
+            bytes[start + 2],
+            bytes[start + 3],
+        ]
+    };
+
+    let dimensions = [0, 1, 2].map(|axis| u32::from_le_bytes(word(axis)) as usize);
+    if dimensions.contains(&0) {
+        return Err(format!("grid has an empty dimension: {dimensions:?}"));
+    }
+
+    let count = dimensions
+        .iter()
+        .try_fold(1usize, |acc, &length| acc.checked_mul(length))
+        .ok_or_else(|| format!("grid dimensions {dimensions:?} are too large"))?;
+    let expected = count
+        .checked_mul(4)
+        .and_then(|size| size.checked_add(HEADER_SIZE))
+        .ok_or_else(|| format!("grid dimensions {dimensions:?} are too large"))?;
+    if bytes.len() != expected {
+        return Err(format!(
+            "expected {expected} bytes for a {dimensions:?} grid, found {}",
+            bytes.len()
+        ));
+    }
+
+    let values: Vec<Float> = (0..count)
+        .map(|index| f32::from_le_bytes(word(3 + index)) as Float)
+        .collect();
+
+    if let Some(index) = values
+        .iter()
+        .position(|value| !value.is_finite() || *value < 0.0)
+    {
+        return Err(format!(
+            "voxel {index} has an invalid density of {}",
+            values[index]
+        ));
+    }
+
+    Ok(Grid::new(dimensions, values))
+}
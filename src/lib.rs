@@ -5,6 +5,7 @@ pub mod io;
 pub mod material;
 pub mod render;
 pub mod scene;
+pub mod texture;
 pub mod transform;
 pub mod utils;
 
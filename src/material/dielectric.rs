@@ -1,15 +1,119 @@
 use super::*;
+use spectrum::REFERENCE_WAVELENGTH;
 use std::sync::Arc;
 
+/// How the refractive index of a material changes with the wavelength of light, which spreads white
+/// light into its colors. Wavelengths are given in micrometers to the formulas.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RefractiveIndex {
+    Constant(Float),
+    /// n = a + b / λ²
+    Cauchy {
+        a: Float,
+        b: Float,
+    },
+    /// n² = 1 + Σ b λ² / (λ² - c)
+    Sellmeier {
+        b: [Float; 3],
+        c: [Float; 3],
+    },
+}
+
+impl RefractiveIndex {
+    /// Schott N-BK7, a common optical glass
+    pub fn crown_glass() -> Self {
+        Self::Sellmeier {
+            b: [1.039_612, 0.231_792_34, 1.010_469_5],
+            c: [0.006_000_699, 0.020_017_914, 103.560_65],
+        }
+    }
+
+    /// Schott N-SF11, a glass with strong dispersion
+    pub fn flint_glass() -> Self {
+        Self::Sellmeier {
+            b: [1.737_596_9, 0.313_747_35, 1.898_781],
+            c: [0.013_188_707, 0.062_306_814, 155.236_3],
+        }
+    }
+
+    pub fn fused_silica() -> Self {
+        Self::Sellmeier {
+            b: [0.696_166_3, 0.407_942_6, 0.897_479_4],
+            c: [0.004_679_148, 0.013_512_063, 97.934_003],
+        }
+    }
+
+    pub fn diamond() -> Self {
+        Self::Sellmeier {
+            b: [0.330_6, 4.335_6, 0.0],
+            c: [0.030_625, 0.011_236, 0.0],
+        }
+    }
+
+    pub fn water() -> Self {
+        Self::Cauchy {
+            a: 1.324,
+            b: 0.003_1,
+        }
+    }
+
+    /// The index at a wavelength in nanometers, or at the Fraunhofer d line if there's none
+    pub fn at(&self, wavelength: Option<Float>) -> Float {
+        let micrometers = wavelength.unwrap_or(REFERENCE_WAVELENGTH) / 1000.0;
+        let squared = micrometers * micrometers;
+
+        match self {
+            RefractiveIndex::Constant(index) => *index,
+            RefractiveIndex::Cauchy { a, b } => a + b / squared,
+            RefractiveIndex::Sellmeier { b, c } => {
+                let sum: Float = b
+                    .iter()
+                    .zip(c)
+                    .map(|(b, c)| b * squared / (squared - c))
+                    .sum();
+
+                (1.0 + sum).sqrt()
+            }
+        }
+    }
+}
+
+/// A clear material, such as glass or water, that reflects or refracts rays according to the Fresnel
+/// equations, and optionally tints the light travelling through it
 #[derive(Debug, Clone, Copy)]
 pub struct Dielectric {
-    refraction_index_ratio: Float,
+    refraction_index: RefractiveIndex,
+    /// How quickly each channel is absorbed inside the material, per unit of distance
+    absorption: Option<Color>,
 }
 
 impl Dielectric {
     pub fn new(refraction_index: Float) -> Arc<Self> {
+        Self::dispersive(RefractiveIndex::Constant(refraction_index))
+    }
+
+    /// Builds a dielectric whose refractive index depends on the wavelength. Dispersion only shows up
+    /// when rendering spectrally, otherwise the index at the Fraunhofer d line is used.
+    pub fn dispersive(refraction_index: RefractiveIndex) -> Arc<Self> {
         Arc::new(Self {
-            refraction_index_ratio: refraction_index,
+            refraction_index,
+            absorption: None,
+        })
+    }
+
+    /// Makes a copy of this dielectric that absorbs light as it travels inside it, following the
+    /// Beer-Lambert law, such that white light comes out as `color` after going through `distance` units
+    /// of the material. When rendering spectrally, absorption is spread over the wavelengths.
+    /// Will error out if the distance isn't positive, or if any channel of the color isn't in (0, 1]
+    pub fn with_absorption(&self, color: Color, distance: Float) -> Arc<Self> {
+        assert! { distance > 0.0 }
+        assert! { color.data.iter().all(|&channel| channel > 0.0 && channel <= 1.0) }
+
+        Arc::new(Self {
+            absorption: Some(Color {
+                data: color.data.map(|channel| -channel.ln() / distance),
+            }),
+            ..*self
         })
     }
 
@@ -31,21 +135,54 @@ impl Dielectric {
 
         ray_perpendicular - ray_parallel
     }
+
+    /// Light that reaches the surface from inside has been travelling through the material, and was
+    /// absorbed along the way
+    fn transmittance(&self, ray: &Ray, collision: &Collision) -> Color {
+        match self.absorption {
+            Some(absorption) if !collision.is_front_facing => {
+                let distance = collision.t * ray.direction.norm();
+                match ray.wavelength {
+                    Some(wavelength) => {
+                        let coefficient = spectrum::color_at(absorption, wavelength);
+                        color::WHITE * (-coefficient * distance).exp()
+                    }
+                    None => Color {
+                        data: absorption
+                            .data
+                            .map(|coefficient| (-coefficient * distance).exp()),
+                    },
+                }
+            }
+            _ => color::WHITE,
+        }
+    }
 }
 
 impl Material for Dielectric {
-    fn scatter(&self, ray: &Ray, collision: &Collision) -> Option<Scatter> {
-        let mut ratio = self.refraction_index_ratio;
+    fn sample(&self, ray: &Ray, collision: &Collision) -> Option<Scatter> {
+        let mut ratio = self.refraction_index.at(ray.wavelength);
         if collision.is_front_facing {
             ratio = 1.0 / ratio;
         }
 
         let unit_direction = ray.direction.normalize();
-        let refracted = Dielectric::refract(unit_direction, collision.normal.normalize(), ratio);
+        let normal = collision.normal.normalize();
+
+        // Picking between reflection and refraction with the Fresnel reflectance as probability makes
+        // both weights cancel out
+        let reflectance = math::fresnel_dielectric(-unit_direction.dot(&normal), ratio);
+        let direction = if random::random_float() < reflectance {
+            Dielectric::reflect(unit_direction, normal)
+        } else {
+            Dielectric::refract(unit_direction, normal, ratio)
+        };
 
         Some(Scatter {
-            scattered: Ray::new(collision.point, refracted.normalize()),
-            attenuation: color::WHITE,
+            scattered: ray.spawn(collision.point, direction.normalize()),
+            attenuation: self.transmittance(ray, collision),
+            pdf: 0.0,
+            is_specular: true,
         })
     }
 }
@@ -1,62 +1,196 @@
 use super::*;
-use math::ZERO_TOL;
+use math::{PI, ZERO_TOL};
 use std::sync::Arc;
+use texture::{Constant, Texture};
 
 /// Idealization of matte surfaces: always scatters light randomly when hit
-#[derive(Debug, Clone, Copy)]
+#[derive(Clone)]
 pub struct Lambertian {
-    albedo: Color,
+    albedo: Arc<dyn Texture>,
 }
 
 impl Lambertian {
     pub fn new(albedo: Color) -> Arc<Self> {
+        Self::textured(Constant::new(albedo))
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture>) -> Arc<Self> {
         Arc::new(Self { albedo })
     }
 }
 
+/// Density of a cosine weighted direction around the normal
+fn cosine_pdf(collision: &Collision, direction: &Vector) -> Float {
+    collision.normal.dot(&direction.normalize()).max(0.0) / PI
+}
+
+/// Samples a cosine weighted direction around the normal
+fn sample_cosine(collision: &Collision) -> Vector {
+    let direction = collision.normal + random::random_unit_vector();
+    if direction.norm_squared() < ZERO_TOL {
+        collision.normal
+    } else {
+        direction
+    }
+}
+
 impl Material for Lambertian {
-    fn scatter(&self, _ray: &Ray, collision: &Collision) -> Option<Scatter> {
-        let mut scatter_direction = collision.normal + random::random_unit_vector();
-        if scatter_direction.norm_squared() < ZERO_TOL {
-            scatter_direction = collision.normal;
-        }
+    fn sample(&self, ray: &Ray, collision: &Collision) -> Option<Scatter> {
+        let scatter_direction = sample_cosine(collision);
 
         Some(Scatter {
-            scattered: Ray::new(collision.point, scatter_direction),
-            attenuation: self.albedo,
+            scattered: ray.spawn(collision.point, scatter_direction),
+            attenuation: self.albedo.value(collision.uv, &collision.point),
+            pdf: cosine_pdf(collision, &scatter_direction),
+            is_specular: false,
         })
     }
+
+    fn eval(&self, _ray: &Ray, collision: &Collision, direction: &Vector) -> Color {
+        self.albedo.value(collision.uv, &collision.point) * cosine_pdf(collision, direction)
+    }
+
+    fn pdf(&self, _ray: &Ray, collision: &Collision, direction: &Vector) -> Float {
+        cosine_pdf(collision, direction)
+    }
 }
 
 /// Behaves more like glossy surfaces, will reflect light rays at glancing angles.
-#[derive(Debug, Clone, Copy)]
+#[derive(Clone)]
 pub struct Glossy {
-    albedo: Color,
+    albedo: Arc<dyn Texture>,
 }
 
 impl Glossy {
     pub fn new(albedo: Color) -> Arc<Self> {
+        Self::textured(Constant::new(albedo))
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture>) -> Arc<Self> {
         Arc::new(Self { albedo })
     }
+
+    fn reflection_probability(ray: &Ray, collision: &Collision) -> Float {
+        1.0 - ray.direction.normalize().dot(&collision.normal).abs()
+    }
 }
 
 impl Material for Glossy {
-    fn scatter(&self, ray: &Ray, collision: &Collision) -> Option<Scatter> {
-        let reflection_probability = 1.0 - ray.direction.normalize().dot(&collision.normal).abs();
+    fn sample(&self, ray: &Ray, collision: &Collision) -> Option<Scatter> {
+        let reflection_probability = Self::reflection_probability(ray, collision);
+
+        if random::random_float() < reflection_probability {
+            return Some(Scatter {
+                scattered: ray.spawn(collision.point, ray.direction.reflect(collision.normal)),
+                attenuation: self.albedo.value(collision.uv, &collision.point),
+                pdf: 0.0,
+                is_specular: true,
+            });
+        }
+
+        let scatter_direction = sample_cosine(collision);
+
+        Some(Scatter {
+            scattered: ray.spawn(collision.point, scatter_direction),
+            attenuation: self.albedo.value(collision.uv, &collision.point),
+            pdf: (1.0 - reflection_probability) * cosine_pdf(collision, &scatter_direction),
+            is_specular: false,
+        })
+    }
+
+    fn eval(&self, ray: &Ray, collision: &Collision, direction: &Vector) -> Color {
+        self.albedo.value(collision.uv, &collision.point) * self.pdf(ray, collision, direction)
+    }
 
-        let mut scatter_direction = if random::random_float() < reflection_probability {
-            ray.direction.reflect(collision.normal)
+    /// Only accounts for the diffuse part, since the reflection is specular
+    fn pdf(&self, ray: &Ray, collision: &Collision, direction: &Vector) -> Float {
+        (1.0 - Self::reflection_probability(ray, collision)) * cosine_pdf(collision, direction)
+    }
+}
+
+/// Rough matte surfaces, such as clay or concrete, modeled as V-shaped grooves that scatter like
+/// Lambertian surfaces. Unlike those, they look flatter and reflect more light back towards its source.
+#[derive(Clone)]
+pub struct OrenNayar {
+    albedo: Arc<dyn Texture>,
+    a: Float,
+    b: Float,
+}
+
+impl OrenNayar {
+    /// `sigma` is the standard deviation of the slope of the grooves, in degrees, where 0 is the same as
+    /// a Lambertian surface.
+    /// Will error out if sigma isn't in [0, 90]
+    pub fn new(albedo: Color, sigma: Float) -> Arc<Self> {
+        Self::textured(Constant::new(albedo), sigma)
+    }
+
+    /// Will error out if sigma isn't in [0, 90]
+    pub fn textured(albedo: Arc<dyn Texture>, sigma: Float) -> Arc<Self> {
+        assert! { Range(0.0, 90.0).contains(sigma) }
+
+        let sigma_squared = math::degrees_to_radians(sigma).powi(2);
+        Arc::new(Self {
+            albedo,
+            a: 1.0 - 0.5 * sigma_squared / (sigma_squared + 0.33),
+            b: 0.45 * sigma_squared / (sigma_squared + 0.09),
+        })
+    }
+
+    /// How much brighter or darker the surface is than a Lambertian one, for light leaving along
+    /// `direction` and arriving along the ray
+    fn roughness_factor(&self, ray: &Ray, collision: &Collision, direction: &Vector) -> Float {
+        let normal = collision.normal;
+        let outgoing = -ray.direction.normalize();
+        let incoming = direction.normalize();
+
+        let (cos_outgoing, cos_incoming) = (normal.dot(&outgoing), normal.dot(&incoming));
+        let sin_outgoing = (1.0 - cos_outgoing * cos_outgoing).max(0.0).sqrt();
+        let sin_incoming = (1.0 - cos_incoming * cos_incoming).max(0.0).sqrt();
+
+        // Cosine of the azimuthal angle between both directions, from their projections on the surface
+        let (outgoing_projection, incoming_projection) = (
+            outgoing - cos_outgoing * normal,
+            incoming - cos_incoming * normal,
+        );
+        let lengths = outgoing_projection.norm() * incoming_projection.norm();
+        let cos_azimuth = if lengths > ZERO_TOL {
+            outgoing_projection.dot(&incoming_projection) / lengths
         } else {
-            collision.normal + random::random_unit_vector()
+            0.0
         };
 
-        if scatter_direction.norm_squared() < ZERO_TOL {
-            scatter_direction = collision.normal;
-        }
+        // sin(α) tan(β), where α is the larger of the polar angles and β the smaller one
+        let sin_tan = if cos_incoming > cos_outgoing {
+            sin_outgoing * sin_incoming / cos_incoming.max(ZERO_TOL)
+        } else {
+            sin_incoming * sin_outgoing / cos_outgoing.max(ZERO_TOL)
+        };
+
+        self.a + self.b * cos_azimuth.max(0.0) * sin_tan
+    }
+}
+
+impl Material for OrenNayar {
+    fn sample(&self, ray: &Ray, collision: &Collision) -> Option<Scatter> {
+        let scatter_direction = sample_cosine(collision);
+        let factor = self.roughness_factor(ray, collision, &scatter_direction);
 
+        // Cosine weighted sampling cancels out everything but the albedo and the roughness
         Some(Scatter {
-            scattered: Ray::new(collision.point, scatter_direction),
-            attenuation: self.albedo,
+            scattered: ray.spawn(collision.point, scatter_direction),
+            attenuation: self.albedo.value(collision.uv, &collision.point) * factor,
+            pdf: cosine_pdf(collision, &scatter_direction),
+            is_specular: false,
         })
     }
+
+    fn eval(&self, ray: &Ray, collision: &Collision, direction: &Vector) -> Color {
+        self.albedo.value(collision.uv, &collision.point)
+            * (self.roughness_factor(ray, collision, direction) * cosine_pdf(collision, direction))
+    }
+
+    fn pdf(&self, _ray: &Ray, collision: &Collision, direction: &Vector) -> Float {
+        cosine_pdf(collision, direction)
+    }
 }
@@ -0,0 +1,36 @@
+use super::*;
+use std::sync::Arc;
+use texture::{Constant, Texture};
+
+/// A surface that emits light uniformly in every direction and on both of its sides, without
+/// scattering any incoming light.
+#[derive(Clone)]
+pub struct DiffuseLight {
+    emission: Arc<dyn Texture>,
+}
+
+impl DiffuseLight {
+    /// The emission isn't restricted to [0, 1], which is how brighter lights are made
+    pub fn new(emission: Color) -> Arc<Self> {
+        Self::textured(Constant::new(emission))
+    }
+
+    pub fn textured(emission: Arc<dyn Texture>) -> Arc<Self> {
+        Arc::new(Self { emission })
+    }
+
+    /// Builds a light from its color and a multiplier for its brightness
+    pub fn with_intensity(color: Color, intensity: Float) -> Arc<Self> {
+        Self::new(intensity * color)
+    }
+}
+
+impl Material for DiffuseLight {
+    fn sample(&self, _ray: &Ray, _collision: &Collision) -> Option<Scatter> {
+        None
+    }
+
+    fn emitted(&self, _ray: &Ray, collision: &Collision) -> Color {
+        self.emission.value(collision.uv, &collision.point)
+    }
+}
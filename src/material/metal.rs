@@ -1,45 +1,53 @@
 use super::*;
 use std::sync::Arc;
+use texture::{Constant, Texture};
 
-/// Represents a simplified metal model as a mirror with some level of fuzziness
-#[derive(Debug, Clone, Copy)]
+/// Represents a simplified metal model as a mirror with some level of fuzziness.
+///
+/// The fuzzy reflection has no closed form density, so it's treated as specular and never sampled as
+/// part of direct lighting.
+#[derive(Clone)]
 pub struct Metal {
-    albedo: Color,
+    albedo: Arc<dyn Texture>,
     fuzziness: Float,
 }
 
 impl Metal {
     /// Will error out if fuzziness is smaller than zero
     pub fn new(albedo: Color, fuzziness: Float) -> Arc<Self> {
-        assert! { fuzziness >= 0.0 }
-        Arc::new(Self { albedo, fuzziness })
+        Self::textured(Constant::new(albedo), fuzziness)
     }
 
     pub fn polished(albedo: Color) -> Arc<Self> {
-        Arc::new(Self {
-            albedo,
-            fuzziness: 0.0,
-        })
+        Self::textured(Constant::new(albedo), 0.0)
+    }
+
+    /// Will error out if fuzziness is smaller than zero
+    pub fn textured(albedo: Arc<dyn Texture>, fuzziness: Float) -> Arc<Self> {
+        assert! { fuzziness >= 0.0 }
+        Arc::new(Self { albedo, fuzziness })
     }
 }
 
 impl Material for Metal {
-    fn scatter(&self, ray: &Ray, collision: &Collision) -> Option<Scatter> {
+    fn sample(&self, ray: &Ray, collision: &Collision) -> Option<Scatter> {
         let scattered =
             ray.direction.reflect(collision.normal) + self.fuzziness * random::random_unit_vector();
 
         Some(Scatter {
-            scattered: Ray::new(collision.point, scattered),
-            attenuation: self.albedo,
+            scattered: ray.spawn(collision.point, scattered),
+            attenuation: self.albedo.value(collision.uv, &collision.point),
+            pdf: 0.0,
+            is_specular: true,
         })
     }
 }
 
 /// Represents a metal model where the color changes as reflectance increases
-#[derive(Debug, Clone, Copy)]
+#[derive(Clone)]
 pub struct SpecularMetal {
-    albedo: Color,
-    reflective_albedo: Color,
+    albedo: Arc<dyn Texture>,
+    reflective_albedo: Arc<dyn Texture>,
     normal_reflectance: Float,
     fuzziness: Float,
 }
@@ -52,15 +60,12 @@ impl SpecularMetal {
         normal_reflectance: Float,
         fuzziness: Float,
     ) -> Arc<Self> {
-        assert! { fuzziness >= 0.0 }
-        assert! { Range(0.0, 1.0).contains(normal_reflectance) }
-
-        Arc::new(Self {
-            albedo,
-            reflective_albedo,
+        Self::textured(
+            Constant::new(albedo),
+            Constant::new(reflective_albedo),
             normal_reflectance,
             fuzziness,
-        })
+        )
     }
 
     pub fn polished(
@@ -68,32 +73,48 @@ impl SpecularMetal {
         reflective_albedo: Color,
         normal_reflectance: Float,
     ) -> Arc<Self> {
+        Self::new(albedo, reflective_albedo, normal_reflectance, 0.0)
+    }
+
+    /// Will error out if fuzziness is smaller than zero, or if normal_reflectance isn't in [0, 1.0]
+    pub fn textured(
+        albedo: Arc<dyn Texture>,
+        reflective_albedo: Arc<dyn Texture>,
+        normal_reflectance: Float,
+        fuzziness: Float,
+    ) -> Arc<Self> {
+        assert! { fuzziness >= 0.0 }
+        assert! { Range(0.0, 1.0).contains(normal_reflectance) }
+
         Arc::new(Self {
             albedo,
             reflective_albedo,
             normal_reflectance,
-            fuzziness: 0.0,
+            fuzziness,
         })
     }
 }
 
 impl Material for SpecularMetal {
-    fn scatter(&self, ray: &Ray, collision: &Collision) -> Option<Scatter> {
+    fn sample(&self, ray: &Ray, collision: &Collision) -> Option<Scatter> {
         let scattered =
             ray.direction.reflect(collision.normal) + self.fuzziness * random::random_unit_vector();
 
-        let cos_theta = ray.direction.normalize().dot(&collision.normal);
+        let cos_theta = -ray.direction.normalize().dot(&collision.normal);
         let reflectance = math::schlick(self.normal_reflectance, cos_theta);
 
         let attenuation = if random::random_float() < reflectance {
-            self.albedo
+            &self.albedo
         } else {
-            self.reflective_albedo
+            &self.reflective_albedo
         };
+        let attenuation = attenuation.value(collision.uv, &collision.point);
 
         Some(Scatter {
-            scattered: Ray::new(collision.point, scattered),
+            scattered: ray.spawn(collision.point, scattered),
             attenuation,
+            pdf: 0.0,
+            is_specular: true,
         })
     }
 }
@@ -0,0 +1,427 @@
+use super::*;
+use dielectric::RefractiveIndex;
+use math::PI;
+use std::sync::Arc;
+
+/// Smallest roughness used, since perfectly smooth microfacets would make the distribution a delta
+const MIN_ALPHA: Float = 1e-3;
+
+/// Orthonormal frame around the shading normal, in which the normal is the z axis
+pub(crate) struct Frame {
+    tangent: Vector,
+    bitangent: Vector,
+    normal: Vector,
+}
+
+impl Frame {
+    pub(crate) fn new(normal: Vector) -> Self {
+        let normal = normal.normalize();
+        let (tangent, bitangent) = normal.orthonormal_basis();
+
+        Self {
+            tangent,
+            bitangent,
+            normal,
+        }
+    }
+
+    pub(crate) fn to_local(&self, vector: &Vector) -> Vector {
+        Vector::new(
+            vector.dot(&self.tangent),
+            vector.dot(&self.bitangent),
+            vector.dot(&self.normal),
+        )
+    }
+
+    pub(crate) fn to_world(&self, vector: &Vector) -> Vector {
+        vector.x * self.tangent + vector.y * self.bitangent + vector.z * self.normal
+    }
+}
+
+/// The GGX, or Trowbridge-Reitz, distribution of microfacet normals, with Smith masking-shadowing.
+/// Directions are given in the local frame, pointing away from the surface.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Ggx {
+    alpha: Float,
+}
+
+impl Ggx {
+    /// Roughness is remapped to alpha by squaring it, which makes it perceptually linear
+    pub(crate) fn new(roughness: Float) -> Self {
+        Self {
+            alpha: (roughness * roughness).max(MIN_ALPHA),
+        }
+    }
+
+    /// Density of microfacet normals, with respect to the projected solid angle
+    pub(crate) fn distribution(&self, microfacet: &Vector) -> Float {
+        if microfacet.z <= 0.0 {
+            return 0.0;
+        }
+
+        let alpha_squared = self.alpha * self.alpha;
+        let cos_squared = microfacet.z * microfacet.z;
+        let denominator = cos_squared * (alpha_squared - 1.0) + 1.0;
+
+        alpha_squared / (PI * denominator * denominator)
+    }
+
+    /// Smith's auxiliary function, from which masking is computed
+    fn lambda(&self, direction: &Vector) -> Float {
+        let cos_squared = direction.z * direction.z;
+        if cos_squared <= 0.0 {
+            return Float::INFINITY;
+        }
+
+        let tan_squared = (1.0 - cos_squared).max(0.0) / cos_squared;
+        0.5 * ((1.0 + self.alpha * self.alpha * tan_squared).sqrt() - 1.0)
+    }
+
+    /// Fraction of microfacets visible from a direction
+    pub(crate) fn masking(&self, direction: &Vector) -> Float {
+        1.0 / (1.0 + self.lambda(direction))
+    }
+
+    /// Height-correlated fraction of microfacets visible from both directions
+    pub(crate) fn masking_shadowing(&self, outgoing: &Vector, incoming: &Vector) -> Float {
+        1.0 / (1.0 + self.lambda(outgoing) + self.lambda(incoming))
+    }
+
+    /// Samples a microfacet normal among those visible from `outgoing`, following Heitz's method
+    pub(crate) fn sample_visible(&self, outgoing: &Vector) -> Vector {
+        // Stretching the view direction turns the distribution into that of a hemisphere
+        let view =
+            Vector::new(self.alpha * outgoing.x, self.alpha * outgoing.y, outgoing.z).normalize();
+
+        let length_squared = view.x * view.x + view.y * view.y;
+        let first = if length_squared > 0.0 {
+            Vector::new(-view.y, view.x, 0.0) / length_squared.sqrt()
+        } else {
+            Vector::x()
+        };
+        let second = view.cross(&first);
+
+        // Uniform point on a disk, squeezed to the part of the hemisphere that's visible
+        let radius = random::random_float().sqrt();
+        let phi = 2.0 * PI * random::random_float();
+        let (t1, t2) = (radius * phi.cos(), radius * phi.sin());
+        let s = 0.5 * (1.0 + view.z);
+        let t2 = (1.0 - s) * (1.0 - t1 * t1).sqrt() + s * t2;
+
+        let hemisphere =
+            t1 * first + t2 * second + (1.0 - t1 * t1 - t2 * t2).max(0.0).sqrt() * view;
+
+        Vector::new(
+            self.alpha * hemisphere.x,
+            self.alpha * hemisphere.y,
+            hemisphere.z.max(0.0),
+        )
+        .normalize()
+    }
+
+    /// Density of `sample_visible` picking a microfacet normal, with respect to solid angle
+    pub(crate) fn visible_pdf(&self, outgoing: &Vector, microfacet: &Vector) -> Float {
+        let cos_outgoing = outgoing.dot(microfacet).max(0.0);
+        self.masking(outgoing) * cos_outgoing * self.distribution(microfacet) / outgoing.z
+    }
+}
+
+/// Mirror reflection of a direction pointing away from the surface
+#[inline]
+pub(crate) fn reflect(outgoing: &Vector, normal: &Vector) -> Vector {
+    2.0 * outgoing.dot(normal) * *normal - *outgoing
+}
+
+/// Refraction of a direction pointing away from the surface, through a normal on its side, given the
+/// ratio between the refractive index on that side and the one on the other side
+fn refract(outgoing: &Vector, normal: &Vector, ratio: Float) -> Option<Vector> {
+    let cos_outgoing = outgoing.dot(normal);
+    let sin_squared = ratio * ratio * (1.0 - cos_outgoing * cos_outgoing).max(0.0);
+    if sin_squared >= 1.0 {
+        return None;
+    }
+
+    let cos_transmitted = (1.0 - sin_squared).sqrt();
+    Some((ratio * cos_outgoing - cos_transmitted) * *normal - ratio * *outgoing)
+}
+
+/// A rough metal, made of tiny mirrors oriented following the GGX distribution, whose color comes from
+/// its complex refractive index
+#[derive(Debug, Clone, Copy)]
+pub struct Conductor {
+    eta: Color,
+    k: Color,
+    distribution: Ggx,
+}
+
+impl Conductor {
+    /// Takes the refractive index eta + ik at the red, green and blue wavelengths.
+    /// Will error out if the roughness isn't in [0, 1]
+    pub fn new(eta: Color, k: Color, roughness: Float) -> Arc<Self> {
+        assert! { Range(0.0, 1.0).contains(roughness) }
+
+        Arc::new(Self {
+            eta,
+            k,
+            distribution: Ggx::new(roughness),
+        })
+    }
+
+    pub fn gold(roughness: Float) -> Arc<Self> {
+        Self::new(
+            Color::new(0.143, 0.374, 1.442),
+            Color::new(3.983, 2.385, 1.603),
+            roughness,
+        )
+    }
+
+    pub fn copper(roughness: Float) -> Arc<Self> {
+        Self::new(
+            Color::new(0.200, 0.924, 1.102),
+            Color::new(3.912, 2.452, 2.142),
+            roughness,
+        )
+    }
+
+    pub fn silver(roughness: Float) -> Arc<Self> {
+        Self::new(
+            Color::new(0.155, 0.117, 0.138),
+            Color::new(4.828, 3.122, 2.147),
+            roughness,
+        )
+    }
+
+    pub fn aluminium(roughness: Float) -> Arc<Self> {
+        Self::new(
+            Color::new(1.657, 0.880, 0.521),
+            Color::new(9.224, 6.270, 4.837),
+            roughness,
+        )
+    }
+
+    fn fresnel(&self, cos_theta: Float) -> Color {
+        Color::new(
+            math::fresnel_conductor(cos_theta, self.eta.r, self.k.r),
+            math::fresnel_conductor(cos_theta, self.eta.g, self.k.g),
+            math::fresnel_conductor(cos_theta, self.eta.b, self.k.b),
+        )
+    }
+}
+
+impl Material for Conductor {
+    fn sample(&self, ray: &Ray, collision: &Collision) -> Option<Scatter> {
+        let frame = Frame::new(collision.normal);
+        let outgoing = frame.to_local(&-ray.direction.normalize());
+        if outgoing.z <= 0.0 {
+            return None;
+        }
+
+        let microfacet = self.distribution.sample_visible(&outgoing);
+        let incoming = reflect(&outgoing, &microfacet);
+        if incoming.z <= 0.0 {
+            return None;
+        }
+
+        // With visible normals sampled, the distribution cancels out and only masking is left
+        let masking = self.distribution.masking_shadowing(&outgoing, &incoming)
+            / self.distribution.masking(&outgoing);
+        let pdf = self.distribution.visible_pdf(&outgoing, &microfacet)
+            / (4.0 * outgoing.dot(&microfacet));
+
+        Some(Scatter {
+            scattered: ray.spawn(collision.point, frame.to_world(&incoming)),
+            attenuation: self.fresnel(outgoing.dot(&microfacet)) * masking,
+            pdf,
+            is_specular: false,
+        })
+    }
+
+    fn eval(&self, ray: &Ray, collision: &Collision, direction: &Vector) -> Color {
+        let frame = Frame::new(collision.normal);
+        let outgoing = frame.to_local(&-ray.direction.normalize());
+        let incoming = frame.to_local(&direction.normalize());
+        if outgoing.z <= 0.0 || incoming.z <= 0.0 {
+            return Color::default();
+        }
+
+        let microfacet = (outgoing + incoming).normalize();
+        let value = self.distribution.distribution(&microfacet)
+            * self.distribution.masking_shadowing(&outgoing, &incoming)
+            / (4.0 * outgoing.z);
+
+        self.fresnel(outgoing.dot(&microfacet)) * value
+    }
+
+    fn pdf(&self, ray: &Ray, collision: &Collision, direction: &Vector) -> Float {
+        let frame = Frame::new(collision.normal);
+        let outgoing = frame.to_local(&-ray.direction.normalize());
+        let incoming = frame.to_local(&direction.normalize());
+        if outgoing.z <= 0.0 || incoming.z <= 0.0 {
+            return 0.0;
+        }
+
+        let microfacet = (outgoing + incoming).normalize();
+        self.distribution.visible_pdf(&outgoing, &microfacet) / (4.0 * outgoing.dot(&microfacet))
+    }
+}
+
+/// Frosted glass, made of tiny smooth dielectric facets oriented following the GGX distribution, which
+/// either reflect or refract light according to the Fresnel equations.
+///
+/// Like `Dielectric`, radiance isn't rescaled when crossing the surface, since going in and coming back
+/// out cancels out.
+#[derive(Debug, Clone, Copy)]
+pub struct RoughDielectric {
+    refraction_index: RefractiveIndex,
+    distribution: Ggx,
+}
+
+impl RoughDielectric {
+    /// Will error out if the roughness isn't in [0, 1]
+    pub fn new(refraction_index: Float, roughness: Float) -> Arc<Self> {
+        Self::dispersive(RefractiveIndex::Constant(refraction_index), roughness)
+    }
+
+    /// Will error out if the roughness isn't in [0, 1]
+    pub fn dispersive(refraction_index: RefractiveIndex, roughness: Float) -> Arc<Self> {
+        assert! { Range(0.0, 1.0).contains(roughness) }
+
+        Arc::new(Self {
+            refraction_index,
+            distribution: Ggx::new(roughness),
+        })
+    }
+
+    /// Ratio between the refractive index on the far side of the surface and the one on the ray's side
+    fn relative_index(&self, ray: &Ray, collision: &Collision) -> Float {
+        let index = self.refraction_index.at(ray.wavelength);
+        if collision.is_front_facing {
+            index
+        } else {
+            1.0 / index
+        }
+    }
+
+    /// The microfacet normal that turns `outgoing` into `incoming`, on the side of the surface that
+    /// `outgoing` is on, or None if no facet does so
+    fn microfacet(outgoing: &Vector, incoming: &Vector, relative_index: Float) -> Option<Vector> {
+        let is_reflection = incoming.z > 0.0;
+        let microfacet = if is_reflection {
+            *outgoing + *incoming
+        } else {
+            *outgoing + relative_index * *incoming
+        };
+        if microfacet.norm_squared() <= 0.0 {
+            return None;
+        }
+
+        let microfacet = microfacet.normalize();
+        let microfacet = if microfacet.z < 0.0 {
+            -microfacet
+        } else {
+            microfacet
+        };
+
+        // Facets seen or lit from behind don't contribute
+        let is_backfacing =
+            outgoing.dot(&microfacet) <= 0.0 || incoming.dot(&microfacet) * incoming.z <= 0.0;
+        (!is_backfacing).then_some(microfacet)
+    }
+
+    /// The BSDF times the cosine term, and the density of sampling it, for directions in the local frame
+    fn evaluate(
+        &self,
+        outgoing: &Vector,
+        incoming: &Vector,
+        relative_index: Float,
+    ) -> (Float, Float) {
+        if outgoing.z <= 0.0 || incoming.z == 0.0 {
+            return (0.0, 0.0);
+        }
+        let Some(microfacet) = Self::microfacet(outgoing, incoming, relative_index) else {
+            return (0.0, 0.0);
+        };
+
+        let cos_outgoing = outgoing.dot(&microfacet);
+        let cos_incoming = incoming.dot(&microfacet);
+        let reflectance = math::fresnel_dielectric(cos_outgoing, 1.0 / relative_index);
+        let distribution = self.distribution.distribution(&microfacet);
+        let masking = self.distribution.masking_shadowing(outgoing, incoming);
+        let visible_pdf = self.distribution.visible_pdf(outgoing, &microfacet);
+
+        if incoming.z > 0.0 {
+            let value = reflectance * distribution * masking / (4.0 * outgoing.z);
+            let pdf = reflectance * visible_pdf / (4.0 * cos_outgoing);
+
+            (value, pdf)
+        } else {
+            // Change of variables from the microfacet normal to the refracted direction
+            let denominator = cos_outgoing + relative_index * cos_incoming;
+            let jacobian =
+                relative_index * relative_index * cos_incoming.abs() / (denominator * denominator);
+
+            let value =
+                (1.0 - reflectance) * distribution * masking * cos_outgoing * jacobian / outgoing.z;
+            let pdf = (1.0 - reflectance) * visible_pdf * jacobian;
+
+            (value, pdf)
+        }
+    }
+}
+
+impl Material for RoughDielectric {
+    fn sample(&self, ray: &Ray, collision: &Collision) -> Option<Scatter> {
+        let frame = Frame::new(collision.normal);
+        let outgoing = frame.to_local(&-ray.direction.normalize());
+        if outgoing.z <= 0.0 {
+            return None;
+        }
+
+        let relative_index = self.relative_index(ray, collision);
+        let microfacet = self.distribution.sample_visible(&outgoing);
+        let reflectance = math::fresnel_dielectric(outgoing.dot(&microfacet), 1.0 / relative_index);
+
+        // Picking between reflection and refraction with the Fresnel reflectance as probability
+        let incoming = if random::random_float() < reflectance {
+            reflect(&outgoing, &microfacet)
+        } else {
+            refract(&outgoing, &microfacet, 1.0 / relative_index)?
+        };
+
+        // Reflections that end up below the surface, and refractions that end up above it, are lost
+        let is_reflection = outgoing.dot(&microfacet) * incoming.dot(&microfacet) > 0.0;
+        if is_reflection != (incoming.z > 0.0) {
+            return None;
+        }
+
+        // Both the Fresnel term and the distribution cancel out, leaving only masking
+        let masking = self.distribution.masking_shadowing(&outgoing, &incoming)
+            / self.distribution.masking(&outgoing);
+        let (_, pdf) = self.evaluate(&outgoing, &incoming, relative_index);
+
+        Some(Scatter {
+            scattered: ray.spawn(collision.point, frame.to_world(&incoming)),
+            attenuation: color::WHITE * masking,
+            pdf,
+            is_specular: false,
+        })
+    }
+
+    fn eval(&self, ray: &Ray, collision: &Collision, direction: &Vector) -> Color {
+        let frame = Frame::new(collision.normal);
+        let outgoing = frame.to_local(&-ray.direction.normalize());
+        let incoming = frame.to_local(&direction.normalize());
+
+        let (value, _) = self.evaluate(&outgoing, &incoming, self.relative_index(ray, collision));
+        color::WHITE * value
+    }
+
+    fn pdf(&self, ray: &Ray, collision: &Collision, direction: &Vector) -> Float {
+        let frame = Frame::new(collision.normal);
+        let outgoing = frame.to_local(&-ray.direction.normalize());
+        let incoming = frame.to_local(&direction.normalize());
+
+        let (_, pdf) = self.evaluate(&outgoing, &incoming, self.relative_index(ray, collision));
+        pdf
+    }
+}
@@ -5,13 +5,43 @@ use render::Ray;
 #[derive(Debug, Clone, Copy)]
 pub struct Scatter {
     pub scattered: Ray,
+    /// The BSDF times the cosine term, divided by the density of the sampled direction
     pub attenuation: Color,
+    /// Density of the sampled direction, with respect to solid angle. Meaningless for specular scattering.
+    pub pdf: Float,
+    /// Whether the direction was picked from a delta distribution, such as a perfect mirror, which
+    /// `Material::eval` and `Material::pdf` can't account for
+    pub is_specular: bool,
 }
 
 pub trait Material: std::marker::Send + std::marker::Sync {
-    fn scatter(&self, ray: &Ray, collision: &Collision) -> Option<Scatter>;
+    /// Picks the direction the ray continues in after hitting the surface, or None if it is absorbed
+    fn sample(&self, ray: &Ray, collision: &Collision) -> Option<Scatter>;
+
+    /// The BSDF times the cosine term, for light leaving the surface along `direction` and arriving along
+    /// the ray. Specular components are left out, and materials that only have those return black, which
+    /// is the default.
+    fn eval(&self, _ray: &Ray, _collision: &Collision, _direction: &Vector) -> Color {
+        Color::default()
+    }
+
+    /// Density, with respect to solid angle, of `sample` picking `direction`. Like `eval`, specular
+    /// components are left out and the default is 0.
+    fn pdf(&self, _ray: &Ray, _collision: &Collision, _direction: &Vector) -> Float {
+        0.0
+    }
+
+    /// Light emitted by the surface at the collision point, towards the origin of the ray.
+    /// Most materials don't emit light, so this defaults to black.
+    fn emitted(&self, _ray: &Ray, _collision: &Collision) -> Color {
+        Color::default()
+    }
 }
 
 pub mod dielectric;
 pub mod diffuse;
+pub mod emissive;
 pub mod metal;
+pub mod microfacet;
+pub mod principled;
+pub mod volume;
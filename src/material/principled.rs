@@ -0,0 +1,302 @@
+use super::*;
+use math::PI;
+use microfacet::{Frame, Ggx, RoughDielectric};
+use std::sync::Arc;
+use texture::{Constant, Texture};
+
+/// Parameters of the principled material, following the conventions of glTF and Blender. All of them
+/// are in [0, 1], except for the refractive index.
+#[derive(Debug, Clone, Copy)]
+pub struct PrincipledParameters {
+    pub base_color: Color,
+    /// Blends from a dielectric to a metal, whose reflections take the base color
+    pub metallic: Float,
+    pub roughness: Float,
+    /// Strength of reflections off the dielectric, where 0.5 gives the usual 4% at normal incidence
+    pub specular: Float,
+    /// Soft highlight at grazing angles, as seen on cloth
+    pub sheen: Float,
+    /// Blends the sheen from white to the base color
+    pub sheen_tint: Float,
+    /// Strength of a clear, glossy layer on top of the rest
+    pub clearcoat: Float,
+    pub clearcoat_roughness: Float,
+    /// Blends from an opaque dielectric to glass tinted by the base color
+    pub transmission: Float,
+    pub refraction_index: Float,
+}
+
+impl Default for PrincipledParameters {
+    fn default() -> Self {
+        Self {
+            base_color: Color::new(0.8, 0.8, 0.8),
+            metallic: 0.0,
+            roughness: 0.5,
+            specular: 0.5,
+            sheen: 0.0,
+            sheen_tint: 0.5,
+            clearcoat: 0.0,
+            clearcoat_roughness: 0.03,
+            transmission: 0.0,
+            refraction_index: 1.5,
+        }
+    }
+}
+
+/// An approximation of Disney's principled BSDF, which layers a diffuse base with sheen, a specular
+/// reflection, a clearcoat and transmission, so that most real world materials can be described by the
+/// same few parameters. Unlike Disney's, the clearcoat uses a GGX distribution instead of GTR1, and it
+/// isn't scaled down to a quarter of its strength.
+#[derive(Clone)]
+pub struct Principled {
+    base_color: Arc<dyn Texture>,
+    parameters: PrincipledParameters,
+    specular: Ggx,
+    clearcoat: Ggx,
+    glass: Arc<RoughDielectric>,
+}
+
+// The lobes, in the order used for their weights
+const DIFFUSE: usize = 0;
+const SPECULAR: usize = 1;
+const CLEARCOAT: usize = 2;
+const TRANSMISSION: usize = 3;
+
+impl Principled {
+    /// Will error out if any parameter is outside of [0, 1], or if the refractive index isn't positive
+    pub fn new(parameters: PrincipledParameters) -> Arc<Self> {
+        Self::textured(Constant::new(parameters.base_color), parameters)
+    }
+
+    /// Takes the base color from a texture, ignoring the one in the parameters.
+    /// Will error out if any parameter is outside of [0, 1], or if the refractive index isn't positive
+    pub fn textured(base_color: Arc<dyn Texture>, parameters: PrincipledParameters) -> Arc<Self> {
+        let PrincipledParameters {
+            metallic,
+            roughness,
+            specular,
+            sheen,
+            sheen_tint,
+            clearcoat,
+            clearcoat_roughness,
+            transmission,
+            refraction_index,
+            ..
+        } = parameters;
+
+        let unit = Range(0.0, 1.0);
+        assert! {
+            [metallic, roughness, specular, sheen, sheen_tint, clearcoat, clearcoat_roughness, transmission]
+                .into_iter()
+                .all(|parameter| unit.contains(parameter))
+        }
+        assert! { refraction_index > 0.0 }
+
+        Arc::new(Self {
+            base_color,
+            parameters,
+            specular: Ggx::new(roughness),
+            clearcoat: Ggx::new(clearcoat_roughness),
+            glass: RoughDielectric::new(refraction_index, roughness),
+        })
+    }
+
+    /// How much each lobe contributes. Light reaching the surface from inside can only have come through
+    /// the transmission lobe.
+    fn weights(&self, collision: &Collision) -> [Float; 4] {
+        let PrincipledParameters {
+            metallic,
+            clearcoat,
+            transmission,
+            ..
+        } = self.parameters;
+        let glass = (1.0 - metallic) * transmission;
+
+        if collision.is_front_facing {
+            [
+                (1.0 - metallic) * (1.0 - transmission),
+                1.0 - glass,
+                clearcoat,
+                glass,
+            ]
+        } else {
+            [0.0, 0.0, 0.0, glass]
+        }
+    }
+
+    /// Chance of sampling each lobe, following their weights
+    fn probabilities(&self, collision: &Collision) -> [Float; 4] {
+        // The clearcoat is faint, so it gets a smaller share of the samples
+        let mut weights = self.weights(collision);
+        weights[CLEARCOAT] *= 0.25;
+
+        let total: Float = weights.iter().sum();
+        if total <= 0.0 {
+            return [0.0; 4];
+        }
+
+        weights.map(|weight| weight / total)
+    }
+
+    /// The BSDF times the cosine term for light reflecting off the front of the surface, and the density
+    /// of sampling it, leaving out transmission
+    fn evaluate_reflection(
+        &self,
+        base_color: Color,
+        outgoing: &Vector,
+        incoming: &Vector,
+        weights: &[Float; 4],
+        probabilities: &[Float; 4],
+    ) -> (Color, Float) {
+        if outgoing.z <= 0.0 || incoming.z <= 0.0 {
+            return (Color::default(), 0.0);
+        }
+
+        let PrincipledParameters {
+            metallic,
+            roughness,
+            specular,
+            sheen,
+            sheen_tint,
+            ..
+        } = self.parameters;
+
+        let halfway = (*outgoing + *incoming).normalize();
+        let cos_halfway = incoming.dot(&halfway);
+        let grazing = (1.0 - cos_halfway).max(0.0).powi(5);
+
+        // Diffuse with retroreflection at grazing angles for rough surfaces, plus sheen
+        let retroreflection = 0.5 + 2.0 * roughness * cos_halfway * cos_halfway;
+        let diffuse_fresnel =
+            |cos_theta: Float| 1.0 + (retroreflection - 1.0) * (1.0 - cos_theta).max(0.0).powi(5);
+        let sheen_color = color::WHITE.lerp(&base_color, sheen_tint) * (sheen * grazing);
+        let diffuse = (base_color / PI * diffuse_fresnel(incoming.z) * diffuse_fresnel(outgoing.z)
+            + sheen_color)
+            * incoming.z;
+
+        // Microfacet reflections, whose value is the same for both layers apart from the Fresnel term
+        let reflection = |distribution: &Ggx| {
+            let value = distribution.distribution(&halfway)
+                * distribution.masking_shadowing(outgoing, incoming)
+                / (4.0 * outgoing.z);
+            let pdf = distribution.visible_pdf(outgoing, &halfway) / (4.0 * outgoing.dot(&halfway));
+
+            (value, pdf)
+        };
+
+        let dielectric_reflectance = color::WHITE * (0.08 * specular);
+        let normal_reflectance = dielectric_reflectance.lerp(&base_color, metallic);
+        let specular_fresnel = Color::new(
+            math::schlick(normal_reflectance.r, cos_halfway),
+            math::schlick(normal_reflectance.g, cos_halfway),
+            math::schlick(normal_reflectance.b, cos_halfway),
+        );
+        let (specular_value, specular_pdf) = reflection(&self.specular);
+        let (clearcoat_value, clearcoat_pdf) = reflection(&self.clearcoat);
+        let clearcoat_fresnel = math::schlick(0.04, cos_halfway);
+
+        // Light reflected by the clearcoat never reaches the layers below it
+        let coated = 1.0 - weights[CLEARCOAT] * math::schlick(0.04, outgoing.z);
+        let value = (weights[DIFFUSE] * diffuse
+            + specular_fresnel * (weights[SPECULAR] * specular_value))
+            * coated
+            + color::WHITE * (weights[CLEARCOAT] * clearcoat_fresnel * clearcoat_value);
+        let pdf = probabilities[DIFFUSE] * incoming.z / PI
+            + probabilities[SPECULAR] * specular_pdf
+            + probabilities[CLEARCOAT] * clearcoat_pdf;
+
+        (value, pdf)
+    }
+
+    /// The BSDF times the cosine term, and the density of sampling it, over every lobe
+    fn evaluate(&self, ray: &Ray, collision: &Collision, direction: &Vector) -> (Color, Float) {
+        let base_color = self.base_color.value(collision.uv, &collision.point);
+        let weights = self.weights(collision);
+        let probabilities = self.probabilities(collision);
+
+        let frame = Frame::new(collision.normal);
+        let outgoing = frame.to_local(&-ray.direction.normalize());
+        let incoming = frame.to_local(&direction.normalize());
+        let (mut value, mut pdf) =
+            self.evaluate_reflection(base_color, &outgoing, &incoming, &weights, &probabilities);
+
+        if weights[TRANSMISSION] > 0.0 {
+            // Only light refracted through the glass is tinted, its reflections stay white
+            let glass = self.glass.eval(ray, collision, direction);
+            let tint = if incoming.z < 0.0 {
+                base_color
+            } else {
+                color::WHITE
+            };
+            value += tint.component_mul(&glass) * weights[TRANSMISSION];
+            pdf += probabilities[TRANSMISSION] * self.glass.pdf(ray, collision, direction);
+        }
+
+        (value, pdf)
+    }
+}
+
+impl Material for Principled {
+    fn sample(&self, ray: &Ray, collision: &Collision) -> Option<Scatter> {
+        let probabilities = self.probabilities(collision);
+        let frame = Frame::new(collision.normal);
+        let outgoing = frame.to_local(&-ray.direction.normalize());
+
+        // Picking a lobe to sample, then weighing the direction by all of them
+        let mut choice = random::random_float();
+        let lobe = (DIFFUSE..TRANSMISSION)
+            .find(|&lobe| {
+                choice -= probabilities[lobe];
+                choice < 0.0
+            })
+            .unwrap_or(TRANSMISSION);
+
+        let direction = match lobe {
+            DIFFUSE => {
+                let radius = random::random_float().sqrt();
+                let phi = 2.0 * PI * random::random_float();
+                let local = Vector::new(
+                    radius * phi.cos(),
+                    radius * phi.sin(),
+                    (1.0 - radius * radius).max(0.0).sqrt(),
+                );
+
+                frame.to_world(&local)
+            }
+            SPECULAR | CLEARCOAT => {
+                if outgoing.z <= 0.0 {
+                    return None;
+                }
+
+                let distribution = if lobe == SPECULAR {
+                    &self.specular
+                } else {
+                    &self.clearcoat
+                };
+                let microfacet = distribution.sample_visible(&outgoing);
+                frame.to_world(&microfacet::reflect(&outgoing, &microfacet))
+            }
+            _ => self.glass.sample(ray, collision)?.scattered.direction,
+        };
+
+        let (value, pdf) = self.evaluate(ray, collision, &direction);
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        Some(Scatter {
+            scattered: ray.spawn(collision.point, direction),
+            attenuation: value / pdf,
+            pdf,
+            is_specular: false,
+        })
+    }
+
+    fn eval(&self, ray: &Ray, collision: &Collision, direction: &Vector) -> Color {
+        self.evaluate(ray, collision, direction).0
+    }
+
+    fn pdf(&self, ray: &Ray, collision: &Collision, direction: &Vector) -> Float {
+        self.evaluate(ray, collision, direction).1
+    }
+}
@@ -0,0 +1,81 @@
+use super::*;
+use math::PI;
+use std::sync::Arc;
+use texture::{Constant, Texture};
+
+/// Henyey-Greenstein phase function for participating media. The anisotropy goes from -1 (back scattering)
+/// to 1 (forward scattering), with 0 scattering uniformly in every direction.
+#[derive(Clone)]
+pub struct Isotropic {
+    albedo: Arc<dyn Texture>,
+    anisotropy: Float,
+}
+
+impl Isotropic {
+    pub fn new(albedo: Color) -> Arc<Self> {
+        Self::with_anisotropy(albedo, 0.0)
+    }
+
+    /// Will error out if the anisotropy isn't in (-1, 1)
+    pub fn with_anisotropy(albedo: Color, anisotropy: Float) -> Arc<Self> {
+        Self::textured(Constant::new(albedo), anisotropy)
+    }
+
+    /// Will error out if the anisotropy isn't in (-1, 1)
+    pub fn textured(albedo: Arc<dyn Texture>, anisotropy: Float) -> Arc<Self> {
+        assert! { anisotropy.abs() < 1.0 }
+
+        Arc::new(Self { albedo, anisotropy })
+    }
+
+    /// Density of scattering by an angle with the given cosine, with respect to solid angle
+    fn phase(&self, cos_theta: Float) -> Float {
+        let g = self.anisotropy;
+        let denominator = 1.0 + g * g - 2.0 * g * cos_theta;
+
+        (1.0 - g * g) / (4.0 * PI * denominator * denominator.sqrt())
+    }
+
+    /// Samples the cosine of the scattering angle, by inverting the Henyey-Greenstein distribution
+    fn sample_cos_theta(&self) -> Float {
+        let g = self.anisotropy;
+        let xi = random::random_float();
+        if g.abs() < 1e-3 {
+            return 1.0 - 2.0 * xi;
+        }
+
+        let term = (1.0 - g * g) / (1.0 - g + 2.0 * g * xi);
+        ((1.0 + g * g - term * term) / (2.0 * g)).clamp(-1.0, 1.0)
+    }
+}
+
+impl Material for Isotropic {
+    fn sample(&self, ray: &Ray, collision: &Collision) -> Option<Scatter> {
+        let forward = ray.direction.normalize();
+        let (tangent, bitangent) = forward.orthonormal_basis();
+
+        let cos_theta = self.sample_cos_theta();
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI * random::random_float();
+        let direction = sin_theta * phi.cos() * tangent
+            + sin_theta * phi.sin() * bitangent
+            + cos_theta * forward;
+
+        // The phase function is sampled exactly, so only the albedo is left
+        Some(Scatter {
+            scattered: ray.spawn(collision.point, direction),
+            attenuation: self.albedo.value(collision.uv, &collision.point),
+            pdf: self.phase(cos_theta),
+            is_specular: false,
+        })
+    }
+
+    fn eval(&self, ray: &Ray, collision: &Collision, direction: &Vector) -> Color {
+        self.albedo.value(collision.uv, &collision.point) * self.pdf(ray, collision, direction)
+    }
+
+    fn pdf(&self, ray: &Ray, _collision: &Collision, direction: &Vector) -> Float {
+        let cos_theta = ray.direction.normalize().dot(&direction.normalize());
+        self.phase(cos_theta)
+    }
+}
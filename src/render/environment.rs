@@ -0,0 +1,99 @@
+use super::*;
+use io::image::Image;
+use io::LoadError;
+use math::PI;
+use std::path::Path;
+use std::sync::Arc;
+use texture::{ImageTexture, WrapMode};
+
+/// Light arriving from infinitely far away, seen by every ray that escapes the scene
+pub trait Environment: std::marker::Send + std::marker::Sync {
+    fn color(&self, direction: &Vector) -> Color;
+}
+
+/// The same color in every direction. Black turns the environment off entirely.
+#[derive(Debug, Clone, Copy)]
+pub struct SolidColor {
+    color: Color,
+}
+
+impl SolidColor {
+    pub fn new(color: Color) -> Arc<Self> {
+        Arc::new(Self { color })
+    }
+}
+
+impl Environment for SolidColor {
+    fn color(&self, _direction: &Vector) -> Color {
+        self.color
+    }
+}
+
+/// Vertical blend between a color straight below and a color straight above
+#[derive(Debug, Clone, Copy)]
+pub struct Gradient {
+    bottom: Color,
+    top: Color,
+}
+
+impl Gradient {
+    pub fn new(bottom: Color, top: Color) -> Arc<Self> {
+        Arc::new(Self { bottom, top })
+    }
+
+    /// The white to light blue sky
+    pub fn sky() -> Arc<Self> {
+        Self::new(color::WHITE, Color::new(0.5, 0.7, 1.0))
+    }
+}
+
+impl Environment for Gradient {
+    fn color(&self, direction: &Vector) -> Color {
+        let unit_direction = direction.normalize();
+        let t = (unit_direction.y + 1.0) / 2.0;
+
+        self.bottom.lerp(&self.top, t)
+    }
+}
+
+/// An environment captured in an equirectangular (latitude-longitude) image, with +y as up.
+/// The center of the image faces -z.
+#[derive(Debug, Clone)]
+pub struct EnvironmentMap {
+    texture: Arc<ImageTexture>,
+    rotation: Float,
+    intensity: Float,
+}
+
+impl EnvironmentMap {
+    /// Builds the map from an image, rotated by `rotation` degrees around the y axis and with its radiance
+    /// multiplied by `intensity`
+    pub fn new(image: Image, rotation: Float, intensity: Float) -> Arc<Self> {
+        Arc::new(Self {
+            texture: ImageTexture::new(image, WrapMode::Repeat, WrapMode::Clamp),
+            rotation: math::degrees_to_radians(rotation),
+            intensity,
+        })
+    }
+
+    /// Loads the map from a Radiance (.hdr) file
+    pub fn load(
+        path: impl AsRef<Path>,
+        rotation: Float,
+        intensity: Float,
+    ) -> Result<Arc<Self>, LoadError> {
+        Ok(Self::new(io::hdr::load(path)?, rotation, intensity))
+    }
+}
+
+impl Environment for EnvironmentMap {
+    fn color(&self, direction: &Vector) -> Color {
+        let unit_direction = direction.normalize();
+
+        let azimuth = unit_direction.x.atan2(-unit_direction.z) - self.rotation;
+        let u = (0.5 + azimuth / (2.0 * PI)).rem_euclid(1.0);
+        let v = unit_direction.y.clamp(-1.0, 1.0).asin() / PI + 0.5;
+
+        self.intensity * self.texture.sample(UV(u, v))
+    }
+}
@@ -0,0 +1,39 @@
+use super::*;
+
+/// Shows the normal at the closest collision, mapped from [-1, 1] to [0, 1] in each channel.
+/// Rays that miss see the environment.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Normals;
+
+impl Integrator for Normals {
+    fn radiance(&self, ray: &Ray, world: &dyn Geometry, environment: &dyn Environment) -> Color {
+        match world.collide(ray, Range(0.001, Float::INFINITY)) {
+            Some(collision) => (Color::from(collision.normal) + color::WHITE) / 2.0,
+            None => environment.color(&ray.direction),
+        }
+    }
+}
+
+/// Shows how far away the closest collision is, from white at the camera to black at `max_distance`
+#[derive(Debug, Clone, Copy)]
+pub struct Depth {
+    max_distance: Float,
+}
+
+impl Depth {
+    pub fn new(max_distance: Float) -> Self {
+        Self { max_distance }
+    }
+}
+
+impl Integrator for Depth {
+    fn radiance(&self, ray: &Ray, world: &dyn Geometry, _environment: &dyn Environment) -> Color {
+        match world.collide(ray, Range(0.001, Float::INFINITY)) {
+            Some(collision) => {
+                let distance = collision.t * ray.direction.norm();
+                (1.0 - distance / self.max_distance).max(0.0) * color::WHITE
+            }
+            None => Color::default(),
+        }
+    }
+}
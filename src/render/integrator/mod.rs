@@ -0,0 +1,90 @@
+use super::*;
+use geometry::Collision;
+use math::ZERO_TOL;
+
+pub mod debug;
+pub mod multiple_importance;
+pub mod next_event;
+pub mod recursive;
+pub mod spectral;
+
+// Reexporting useful types
+pub use multiple_importance::MultipleImportance;
+pub use next_event::NextEvent;
+pub use recursive::Recursive;
+pub use spectral::Spectral;
+
+/// A light transport algorithm, which estimates how much light travels back along a camera ray
+pub trait Integrator: std::marker::Send + std::marker::Sync {
+    fn radiance(&self, ray: &Ray, world: &dyn Geometry, environment: &dyn Environment) -> Color;
+}
+
+/// Light arriving at a point from a sampled point on a light source
+struct LightSample {
+    /// Goes from the point being lit to the sample, reaching it at t = 1
+    shadow_ray: Ray,
+    emitted: Color,
+    /// Density of the sample with respect to solid angle, including the choice of light
+    pdf: Float,
+}
+
+/// Picks one of the lights uniformly and samples a point on it as seen from `origin`, returning None if
+/// that point is occluded or the light can't be sampled. The shadow ray continues the path of `ray`.
+fn sample_light(
+    lights: &[WorldObject],
+    ray: &Ray,
+    origin: Point,
+    world: &dyn Geometry,
+) -> Option<LightSample> {
+    if lights.is_empty() {
+        return None;
+    }
+
+    let light = &lights[random::random_index(lights.len())];
+    let sample = light.sample_surface(ray.time)?;
+
+    let shadow_ray = ray.spawn(origin, sample.point - origin);
+    let distance_squared = shadow_ray.direction.norm_squared();
+    let cos_light = sample.normal.dot(&shadow_ray.direction.normalize()).abs();
+    if cos_light <= ZERO_TOL {
+        return None;
+    }
+
+    // The shadow ray reaches the sampled point at t = 1, anything closer is in the way
+    let emitted = match world.collide(&shadow_ray, Range(0.001, 1.001)) {
+        Some(hit) if hit.t >= 0.999 => hit.material.emitted(&shadow_ray, &hit),
+        _ => return None,
+    };
+
+    // Converting the area density of the sample to solid angle, and accounting for the choice of light
+    let pdf = sample.pdf * distance_squared / cos_light / lights.len() as Float;
+
+    Some(LightSample {
+        shadow_ray,
+        emitted,
+        pdf,
+    })
+}
+
+/// Density, with respect to solid angle, of `sample_light` picking the point where `ray` hit `collision`
+fn light_pdf(lights: &[WorldObject], ray: &Ray, collision: &Collision) -> Float {
+    let direction = ray.direction.normalize();
+    let distance_squared = (collision.point - ray.origin).norm_squared();
+    let cos_light = collision.normal.dot(&direction).abs();
+    if cos_light <= ZERO_TOL {
+        return 0.0;
+    }
+
+    let mut pdf = 0.0;
+    for light in lights.iter() {
+        // Only the light that was actually hit contributes
+        let range = Range(collision.t * 0.999, collision.t * 1.001);
+        if light.collide(ray, range).is_some() {
+            pdf += light.surface_pdf(&collision.point, &collision.normal, ray.time)
+                * distance_squared
+                / cos_light;
+        }
+    }
+
+    pdf / lights.len() as Float
+}
@@ -0,0 +1,92 @@
+use super::*;
+
+/// Path tracer that samples both the lights and the materials at every non-specular bounce, weighting
+/// both estimates with the power heuristic. This handles small, bright lights as well as large lights
+/// seen through glossy materials.
+///
+/// Every emissive object in the scene should be among the lights, otherwise it will only be found by
+/// sampling the materials.
+#[derive(Clone)]
+pub struct MultipleImportance {
+    max_depth: usize,
+    lights: Vec<WorldObject>,
+}
+
+impl MultipleImportance {
+    /// Light sources must support `Geometry::sample_surface`, otherwise they're never sampled
+    pub fn new(max_depth: usize, lights: Vec<WorldObject>) -> Self {
+        Self { max_depth, lights }
+    }
+
+    /// Power heuristic with an exponent of 2, as recommended by Veach
+    fn power_heuristic(pdf: Float, other_pdf: Float) -> Float {
+        let (a, b) = (pdf * pdf, other_pdf * other_pdf);
+        if a + b <= 0.0 {
+            return 0.0;
+        }
+
+        a / (a + b)
+    }
+
+    /// Estimates the light arriving at the collision directly from a randomly chosen light source,
+    /// already weighted by the material and the heuristic
+    fn sample_direct(&self, ray: &Ray, collision: &Collision, world: &dyn Geometry) -> Color {
+        let sample = match sample_light(&self.lights, ray, collision.point, world) {
+            Some(sample) => sample,
+            None => return Color::default(),
+        };
+
+        let direction = sample.shadow_ray.direction;
+        let bsdf = collision.material.eval(ray, collision, &direction);
+        let material_pdf = collision.material.pdf(ray, collision, &direction);
+        let weight = Self::power_heuristic(sample.pdf, material_pdf);
+
+        sample.emitted.component_mul(&bsdf) * weight / sample.pdf
+    }
+}
+
+impl Integrator for MultipleImportance {
+    fn radiance(&self, ray: &Ray, world: &dyn Geometry, environment: &dyn Environment) -> Color {
+        let mut ray = *ray;
+        let mut radiance = Color::default();
+        let mut throughput = color::WHITE;
+
+        // The density of the material sample that produced the current ray, None for camera rays and
+        // specular bounces, which light sampling can't produce
+        let mut material_pdf: Option<Float> = None;
+
+        for _ in 0..self.max_depth {
+            let collision = match world.collide(&ray, Range(0.001, Float::INFINITY)) {
+                Some(collision) => collision,
+                None => {
+                    radiance += throughput.component_mul(&environment.color(&ray.direction));
+                    break;
+                }
+            };
+
+            let emitted = collision.material.emitted(&ray, &collision);
+            let weight = match material_pdf {
+                Some(pdf) if emitted != Color::default() => {
+                    Self::power_heuristic(pdf, light_pdf(&self.lights, &ray, &collision))
+                }
+                _ => 1.0,
+            };
+            radiance += throughput.component_mul(&emitted) * weight;
+
+            let scatter = match collision.material.sample(&ray, &collision) {
+                Some(scatter) => scatter,
+                None => break,
+            };
+
+            if !scatter.is_specular {
+                radiance += throughput.component_mul(&self.sample_direct(&ray, &collision, world));
+            }
+
+            material_pdf = (!scatter.is_specular).then_some(scatter.pdf);
+            throughput = throughput.component_mul(&scatter.attenuation);
+            ray = scatter.scattered;
+        }
+
+        radiance
+    }
+}
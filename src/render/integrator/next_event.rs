@@ -0,0 +1,72 @@
+use super::*;
+
+/// Path tracer that, at every diffuse bounce, samples a point on one of the lights and casts a shadow ray
+/// towards it. The material's own sample is then only used to continue the path, so emission it finds
+/// after a diffuse bounce isn't counted again.
+///
+/// Every emissive object in the scene should be among the lights, otherwise it will only be seen directly
+/// or through mirrors.
+#[derive(Clone)]
+pub struct NextEvent {
+    max_depth: usize,
+    lights: Vec<WorldObject>,
+}
+
+impl NextEvent {
+    /// Light sources must support `Geometry::sample_surface`, otherwise they're never sampled
+    pub fn new(max_depth: usize, lights: Vec<WorldObject>) -> Self {
+        Self { max_depth, lights }
+    }
+
+    /// Estimates the light arriving at the collision directly from a randomly chosen light source,
+    /// already weighted by the material
+    fn sample_direct(&self, ray: &Ray, collision: &Collision, world: &dyn Geometry) -> Color {
+        match sample_light(&self.lights, ray, collision.point, world) {
+            Some(sample) => {
+                let direction = sample.shadow_ray.direction;
+                let bsdf = collision.material.eval(ray, collision, &direction);
+
+                sample.emitted.component_mul(&bsdf) / sample.pdf
+            }
+            None => Color::default(),
+        }
+    }
+}
+
+impl Integrator for NextEvent {
+    fn radiance(&self, ray: &Ray, world: &dyn Geometry, environment: &dyn Environment) -> Color {
+        let mut ray = *ray;
+        let mut radiance = Color::default();
+        let mut throughput = color::WHITE;
+        let mut count_emission = true;
+
+        for _ in 0..self.max_depth {
+            let collision = match world.collide(&ray, Range(0.001, Float::INFINITY)) {
+                Some(collision) => collision,
+                None => {
+                    radiance += throughput.component_mul(&environment.color(&ray.direction));
+                    break;
+                }
+            };
+
+            if count_emission {
+                radiance += throughput.component_mul(&collision.material.emitted(&ray, &collision));
+            }
+
+            let scatter = match collision.material.sample(&ray, &collision) {
+                Some(scatter) => scatter,
+                None => break,
+            };
+
+            if !scatter.is_specular {
+                radiance += throughput.component_mul(&self.sample_direct(&ray, &collision, world));
+            }
+
+            count_emission = scatter.is_specular;
+            throughput = throughput.component_mul(&scatter.attenuation);
+            ray = scatter.scattered;
+        }
+
+        radiance
+    }
+}
@@ -0,0 +1,47 @@
+use super::*;
+
+/// Follows a single path, letting each material pick the scattered direction until the path escapes,
+/// gets absorbed or reaches the maximum depth
+#[derive(Debug, Clone, Copy)]
+pub struct Recursive {
+    max_depth: usize,
+}
+
+impl Recursive {
+    pub fn new(max_depth: usize) -> Self {
+        Self { max_depth }
+    }
+
+    fn ray_color(
+        ray: &Ray,
+        world: &dyn Geometry,
+        environment: &dyn Environment,
+        depth: usize,
+    ) -> Color {
+        if depth == 0 {
+            return Color::default();
+        }
+
+        match world.collide(ray, Range(0.001, Float::INFINITY)) {
+            Some(collision) => {
+                let emitted = collision.material.emitted(ray, &collision);
+
+                match collision.material.sample(ray, &collision) {
+                    Some(scatter) => {
+                        emitted
+                            + Self::ray_color(&scatter.scattered, world, environment, depth - 1)
+                                .component_mul(&scatter.attenuation)
+                    }
+                    None => emitted,
+                }
+            }
+            _ => environment.color(&ray.direction),
+        }
+    }
+}
+
+impl Integrator for Recursive {
+    fn radiance(&self, ray: &Ray, world: &dyn Geometry, environment: &dyn Environment) -> Color {
+        Self::ray_color(ray, world, environment, self.max_depth)
+    }
+}
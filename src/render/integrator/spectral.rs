@@ -0,0 +1,66 @@
+use super::*;
+use spectrum::{MAX_WAVELENGTH, MIN_WAVELENGTH};
+
+type Matrix3 = nalgebra::Matrix3<Float>;
+
+/// Steps used to integrate over the visible spectrum when setting up the conversion to color
+const INTEGRATION_STEPS: usize = 4000;
+
+/// Renders with light of a single, randomly picked wavelength per path, so that materials whose
+/// behavior depends on the wavelength, such as dispersive glass, split light into its colors.
+///
+/// The path itself is traced by another integrator, with the wavelength carried by its rays. The color
+/// it finds is then converted to the intensity at that wavelength and back to color through the color
+/// matching functions, in a way that averages out to the very same color whenever nothing along the path
+/// depends on the wavelength.
+#[derive(Clone)]
+pub struct Spectral {
+    integrator: Arc<dyn Integrator>,
+    /// Turns a color into the weights of the color matching functions that make up its spectrum
+    to_spectrum: Matrix3,
+}
+
+impl Spectral {
+    pub fn new(integrator: Arc<dyn Integrator>) -> Self {
+        // Spectra are built out of the color matching functions themselves. Their Gram matrix tells how
+        // these combine back into color, so its inverse makes the round trip exact.
+        let step = (MAX_WAVELENGTH - MIN_WAVELENGTH) / INTEGRATION_STEPS as Float;
+        let gram = (0..INTEGRATION_STEPS)
+            .map(|index| {
+                let response =
+                    spectrum::wavelength_to_rgb(MIN_WAVELENGTH + (index as Float + 0.5) * step)
+                        .data;
+
+                step * response * response.transpose()
+            })
+            .fold(Matrix3::zeros(), |acc, elem| acc + elem);
+
+        Self {
+            integrator,
+            to_spectrum: gram
+                .try_inverse()
+                .expect("The color matching functions are linearly independent"),
+        }
+    }
+}
+
+impl Integrator for Spectral {
+    fn radiance(&self, ray: &Ray, world: &dyn Geometry, environment: &dyn Environment) -> Color {
+        let range = MAX_WAVELENGTH - MIN_WAVELENGTH;
+        let wavelength = MIN_WAVELENGTH + random::random_float() * range;
+
+        let ray = Ray {
+            wavelength: Some(wavelength),
+            ..*ray
+        };
+        let radiance = self.integrator.radiance(&ray, world, environment);
+
+        let response = spectrum::wavelength_to_rgb(wavelength).data;
+        let intensity = response.dot(&(self.to_spectrum * radiance.data));
+
+        // Dividing by the density of the wavelength, which is uniform
+        Color {
+            data: range * intensity * response,
+        }
+    }
+}
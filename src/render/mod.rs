@@ -1,11 +1,16 @@
 use super::*;
 use geometry::Geometry;
 use io::PngTile;
+use std::sync::Arc;
 
+pub mod environment;
+pub mod integrator;
 pub mod pinhole;
 pub mod thin_lens;
 
 // Reexporting useful types
+pub use environment::Environment;
+pub use integrator::Integrator;
 pub use pinhole::Pinhole;
 pub use thin_lens::ThinLens;
 
@@ -13,28 +18,43 @@ pub trait Camera: std::marker::Send + std::marker::Sync {
     fn cast(&self, u: Float, v: Float) -> Ray;
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Clone)]
 pub struct Renderer<C>
 where
     C: Camera,
 {
     camera: C,
     samples_per_pixel: usize,
-    max_depth: usize,
+    integrator: Arc<dyn Integrator>,
+    environment: Arc<dyn Environment>,
 }
 
 impl<C> Renderer<C>
 where
     C: Camera,
 {
+    /// Builds a renderer that uses the recursive integrator and is lit by the default sky gradient
     pub fn new(camera: C, samples_per_pixel: usize, max_depth: usize) -> Self {
         Self {
             camera,
             samples_per_pixel,
-            max_depth,
+            integrator: Arc::new(integrator::Recursive::new(max_depth)),
+            environment: environment::Gradient::sky(),
         }
     }
 
+    /// Replaces the algorithm used to compute the color of each camera ray
+    pub fn with_integrator(mut self, integrator: Arc<dyn Integrator>) -> Self {
+        self.integrator = integrator;
+        self
+    }
+
+    /// Replaces the light arriving from outside the scene
+    pub fn with_environment(mut self, environment: Arc<dyn Environment>) -> Self {
+        self.environment = environment;
+        self
+    }
+
     fn jitter_batch(&self) -> Vec<(Float, Float)> {
         let mut jitter = Vec::new();
 
@@ -63,7 +83,7 @@ where
 
                 for (du, dv) in self.jitter_batch().iter() {
                     let ray = self.camera.cast(i as Float + du, j as Float + dv);
-                    color += ray_color(&ray, geometry, self.max_depth);
+                    color += self.integrator.radiance(&ray, geometry, &*self.environment);
                 }
 
                 color /= self.samples_per_pixel as Float;
@@ -75,36 +95,40 @@ where
     }
 }
 
-fn ray_color(ray: &Ray, world: &dyn Geometry, depth: usize) -> Color {
-    if depth == 0 {
-        return Color::default();
-    }
-
-    match world.collide(ray, Range(0.001, Float::INFINITY)) {
-        Some(collision) => match collision.material.scatter(ray, &collision) {
-            Some(scatter) => {
-                ray_color(&scatter.scattered, world, depth - 1).component_mul(&scatter.attenuation)
-            }
-            None => Color::default(),
-        },
-        _ => {
-            let unit_direction = ray.direction.normalize();
-            let t = (unit_direction.y + 1.0) / 2.0;
-
-            color::WHITE.lerp(&Color::new(0.5, 0.7, 1.0), t)
-        }
-    }
-}
-
 #[derive(Debug, Clone, Copy)]
 pub struct Ray {
     pub origin: Point,
     pub direction: Vector,
+    /// The instant the ray was cast at, which moving geometry uses to pick where it is
+    pub time: Float,
+    /// Wavelength of the light carried by the ray, in nanometers, when rendering spectrally. None stands
+    /// for the usual mix of red, green and blue.
+    pub wavelength: Option<Float>,
 }
 
 impl Ray {
+    /// Builds a ray cast at time 0
     pub fn new(origin: Point, direction: Vector) -> Self {
-        Self { origin, direction }
+        Self::with_time(origin, direction, 0.0)
+    }
+
+    pub fn with_time(origin: Point, direction: Vector, time: Float) -> Self {
+        Self {
+            origin,
+            direction,
+            time,
+            wavelength: None,
+        }
+    }
+
+    /// Builds a ray that continues the path of this one, such as a bounce or a shadow ray, so it
+    /// happens at the same time and carries the same wavelength
+    pub fn spawn(&self, origin: Point, direction: Vector) -> Self {
+        Self {
+            origin,
+            direction,
+            ..*self
+        }
     }
 
     pub fn at(&self, t: Float) -> Point {
@@ -6,6 +6,8 @@ pub struct Pinhole {
     upper_left_pixel: Point,
     pixel_du: Vector,
     pixel_dv: Vector,
+    /// Rays are cast at times picked uniformly in this range
+    shutter: Range,
 }
 
 impl Pinhole {
@@ -49,8 +51,17 @@ impl Pinhole {
             upper_left_pixel,
             pixel_du,
             pixel_dv,
+            shutter: Range(0.0, 0.0),
         }
     }
+
+    /// Keeps the shutter open from `open` to `close`, blurring geometry that moves in the meantime.
+    /// Will error out if the shutter closes before it opens
+    pub fn with_shutter(mut self, open: Float, close: Float) -> Self {
+        assert! { open <= close }
+        self.shutter = Range(open, close);
+        self
+    }
 }
 
 impl Camera for Pinhole {
@@ -58,6 +69,9 @@ impl Camera for Pinhole {
         let sample = self.upper_left_pixel + u * self.pixel_du + v * self.pixel_dv;
         let origin = self.center;
 
-        Ray::new(origin, sample - origin)
+        let Range(open, close) = self.shutter;
+        let time = open + random::random_float() * (close - open);
+
+        Ray::with_time(origin, sample - origin, time)
     }
 }
@@ -10,6 +10,8 @@ pub struct ThinLens {
     pixel_dv: Vector,
     defocus_du: Vector,
     defocus_dv: Vector,
+    /// Rays are cast at times picked uniformly in this range
+    shutter: Range,
 }
 
 impl ThinLens {
@@ -67,9 +69,18 @@ impl ThinLens {
             pixel_dv,
             defocus_du,
             defocus_dv,
+            shutter: Range(0.0, 0.0),
         }
     }
 
+    /// Keeps the shutter open from `open` to `close`, blurring geometry that moves in the meantime.
+    /// Will error out if the shutter closes before it opens
+    pub fn with_shutter(mut self, open: Float, close: Float) -> Self {
+        assert! { open <= close }
+        self.shutter = Range(open, close);
+        self
+    }
+
     fn sample_defocus_disk(&self) -> Point {
         let p = random::random_in_unit_disk();
         self.center + p.x * self.defocus_du + p.y * self.defocus_dv
@@ -81,6 +92,9 @@ impl Camera for ThinLens {
         let sample = self.upper_left_pixel + u * self.pixel_du + v * self.pixel_dv;
         let origin = self.sample_defocus_disk();
 
-        Ray::new(origin, sample - origin)
+        let Range(open, close) = self.shutter;
+        let time = open + random::random_float() * (close - open);
+
+        Ray::with_time(origin, sample - origin, time)
     }
 }
@@ -1,7 +1,7 @@
 use super::*;
 use bounding::hierarchy::BoundingHierarchy;
 use geometry::{flat::Parallelogram, sphere::Ellipsoid};
-use material::{dielectric::Dielectric, diffuse::Lambertian, metal::Metal};
+use material::{dielectric::Dielectric, diffuse::Lambertian, emissive::DiffuseLight, metal::Metal};
 use render::{Pinhole, ThinLens};
 use std::sync::Arc;
 
@@ -56,3 +56,63 @@ pub fn make_world2(image_dimensions: Dimensions) -> (Pinhole, Arc<BoundingHierar
         BoundingHierarchy::from_vec(&mut world),
     )
 }
+
+/// The classic Cornell box: a closed room with a red and a green wall, lit only by a ceiling light.
+/// The light is also returned separately, so that it can be sampled directly.
+pub fn make_cornell_box(
+    image_dimensions: Dimensions,
+) -> (Pinhole, Arc<BoundingHierarchy>, Vec<WorldObject>) {
+    let red = Lambertian::new(Color::new(0.65, 0.05, 0.05));
+    let white = Lambertian::new(Color::new(0.73, 0.73, 0.73));
+    let green = Lambertian::new(Color::new(0.12, 0.45, 0.15));
+    let light: WorldObject = Parallelogram::new(
+        Point::new(343.0, 554.0, 332.0),
+        -130.0 * Vector::x(),
+        -105.0 * Vector::z(),
+        DiffuseLight::new(Color::new(15.0, 15.0, 15.0)),
+    );
+
+    let mut world: Vec<WorldObject> = vec![
+        Parallelogram::new(
+            Point::new(555.0, 0.0, 0.0),
+            555.0 * Vector::y(),
+            555.0 * Vector::z(),
+            green,
+        ),
+        Parallelogram::new(
+            Point::zeros(),
+            555.0 * Vector::y(),
+            555.0 * Vector::z(),
+            red,
+        ),
+        light.clone(),
+        Parallelogram::new(
+            Point::zeros(),
+            555.0 * Vector::x(),
+            555.0 * Vector::z(),
+            white.clone(),
+        ),
+        Parallelogram::new(
+            Point::new(555.0, 555.0, 555.0),
+            -555.0 * Vector::x(),
+            -555.0 * Vector::z(),
+            white.clone(),
+        ),
+        Parallelogram::new(
+            Point::new(0.0, 0.0, 555.0),
+            555.0 * Vector::x(),
+            555.0 * Vector::y(),
+            white,
+        ),
+    ];
+
+    let camera = Pinhole::new(
+        image_dimensions,
+        Point::new(278.0, 278.0, -800.0),
+        Point::new(278.0, 278.0, 0.0),
+        Vector::y(),
+        40.0,
+    );
+
+    (camera, BoundingHierarchy::from_vec(&mut world), vec![light])
+}
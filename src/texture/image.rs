@@ -0,0 +1,101 @@
+use super::*;
+use io::image::Image;
+use io::LoadError;
+use std::path::Path;
+
+/// What happens to surface parameters outside of [0, 1]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum WrapMode {
+    /// Tiles the image
+    #[default]
+    Repeat,
+    /// Tiles the image, mirroring every other tile so that there are no seams
+    Mirror,
+    /// Stretches the pixels on the border
+    Clamp,
+}
+
+impl WrapMode {
+    /// Maps a pixel coordinate into [0, length)
+    fn apply(&self, coordinate: isize, length: usize) -> usize {
+        let length = length as isize;
+
+        let wrapped = match self {
+            WrapMode::Repeat => coordinate.rem_euclid(length),
+            WrapMode::Mirror => {
+                let period = coordinate.rem_euclid(2 * length);
+                if period < length {
+                    period
+                } else {
+                    2 * length - 1 - period
+                }
+            }
+            WrapMode::Clamp => coordinate.clamp(0, length - 1),
+        };
+
+        wrapped as usize
+    }
+}
+
+/// A bilinearly filtered image, where (0, 0) is the bottom left corner and (1, 1) the top right one
+#[derive(Debug, Clone)]
+pub struct ImageTexture {
+    image: Image,
+    wrap_u: WrapMode,
+    wrap_v: WrapMode,
+}
+
+impl ImageTexture {
+    /// Expects an image in linear color space.
+    /// Will error out if the image is empty
+    pub fn new(image: Image, wrap_u: WrapMode, wrap_v: WrapMode) -> Arc<Self> {
+        let Dimensions(width, height) = image.dimensions();
+        assert! { width > 0 && height > 0 }
+
+        Arc::new(Self {
+            image,
+            wrap_u,
+            wrap_v,
+        })
+    }
+
+    /// Loads a PNG file, assuming it was encoded with a gamma of 2.2
+    pub fn load(path: impl AsRef<Path>, wrap: WrapMode) -> Result<Arc<Self>, LoadError> {
+        let image = io::image::load_png(path)?.map(|color| Color {
+            data: color.data.map(|channel| channel.powf(2.2)),
+        });
+
+        Ok(Self::new(image, wrap, wrap))
+    }
+
+    /// Bilinearly filters the image at the given parameters
+    pub fn sample(&self, uv: UV) -> Color {
+        let Dimensions(width, height) = self.image.dimensions();
+        let UV(u, v) = uv;
+
+        // Pixel centers are at half integers, and rows are stored from the top
+        let x = u * width as Float - 0.5;
+        let y = (1.0 - v) * height as Float - 0.5;
+
+        let (x0, y0) = (x.floor(), y.floor());
+        let (dx, dy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as isize, y0 as isize);
+
+        let (x1, y1) = (
+            self.wrap_u.apply(x0 + 1, width),
+            self.wrap_v.apply(y0 + 1, height),
+        );
+        let (x0, y0) = (self.wrap_u.apply(x0, width), self.wrap_v.apply(y0, height));
+
+        let top = self.image.get(x0, y0).lerp(&self.image.get(x1, y0), dx);
+        let bottom = self.image.get(x0, y1).lerp(&self.image.get(x1, y1), dx);
+
+        top.lerp(&bottom, dy)
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, uv: UV, _point: &Point) -> Color {
+        self.sample(uv)
+    }
+}
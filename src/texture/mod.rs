@@ -0,0 +1,70 @@
+use super::*;
+use std::sync::Arc;
+
+pub mod image;
+pub mod noise;
+
+// Reexporting useful types
+pub use image::{ImageTexture, WrapMode};
+pub use noise::{Noise, NoisePattern, Perlin};
+
+/// A color that varies over a surface, looked up either by surface parameters or by position
+pub trait Texture: std::marker::Send + std::marker::Sync {
+    fn value(&self, uv: UV, point: &Point) -> Color;
+}
+
+/// The same color everywhere
+#[derive(Debug, Clone, Copy)]
+pub struct Constant {
+    color: Color,
+}
+
+impl Constant {
+    pub fn new(color: Color) -> Arc<Self> {
+        Arc::new(Self { color })
+    }
+}
+
+impl Texture for Constant {
+    fn value(&self, _uv: UV, _point: &Point) -> Color {
+        self.color
+    }
+}
+
+/// A 3D checkerboard of cubes with sides of length `scale`, alternating between two textures.
+/// Since it depends on position only, it works on surfaces without parameters.
+#[derive(Clone)]
+pub struct Checker {
+    inverse_scale: Float,
+    even: Arc<dyn Texture>,
+    odd: Arc<dyn Texture>,
+}
+
+impl Checker {
+    /// Will error out if the scale is 0
+    pub fn new(scale: Float, even: Arc<dyn Texture>, odd: Arc<dyn Texture>) -> Arc<Self> {
+        assert! { scale.abs() > math::ZERO_TOL }
+
+        Arc::new(Self {
+            inverse_scale: 1.0 / scale,
+            even,
+            odd,
+        })
+    }
+
+    pub fn from_colors(scale: Float, even: Color, odd: Color) -> Arc<Self> {
+        Self::new(scale, Constant::new(even), Constant::new(odd))
+    }
+}
+
+impl Texture for Checker {
+    fn value(&self, uv: UV, point: &Point) -> Color {
+        let cell = (self.inverse_scale * point.data).map(|x| x.floor() as i64);
+
+        if (cell.x + cell.y + cell.z).rem_euclid(2) == 0 {
+            self.even.value(uv, point)
+        } else {
+            self.odd.value(uv, point)
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use super::*;
+
+const POINT_COUNT: usize = 256;
+
+/// Ken Perlin's gradient noise, with random unit gradients on a lattice of period 256
+#[derive(Debug, Clone)]
+pub struct Perlin {
+    gradients: Vec<Vector>,
+    permutations: [Vec<usize>; 3],
+}
+
+impl Default for Perlin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Perlin {
+    pub fn new() -> Self {
+        let gradients = (0..POINT_COUNT)
+            .map(|_| random::random_unit_vector())
+            .collect();
+
+        Self {
+            gradients,
+            permutations: [
+                Self::permutation(),
+                Self::permutation(),
+                Self::permutation(),
+            ],
+        }
+    }
+
+    fn permutation() -> Vec<usize> {
+        let mut permutation: Vec<usize> = (0..POINT_COUNT).collect();
+
+        // Fisher-Yates shuffle
+        for i in (1..POINT_COUNT).rev() {
+            permutation.swap(i, random::random_index(i + 1));
+        }
+
+        permutation
+    }
+
+    /// Smooth noise in roughly [-1, 1]
+    pub fn noise(&self, point: &Point) -> Float {
+        let floor = point.data.map(|x| x.floor());
+        let fraction = point.data - floor;
+
+        // Hermite smoothing of the interpolation weights hides the lattice
+        let weights = fraction.map(|x| x * x * (3.0 - 2.0 * x));
+
+        let mut accumulator = 0.0;
+        for i in 0..2 {
+            for j in 0..2 {
+                for k in 0..2 {
+                    let [px, py, pz] = &self.permutations;
+                    let index = px[(floor.x as i64 + i).rem_euclid(256) as usize]
+                        ^ py[(floor.y as i64 + j).rem_euclid(256) as usize]
+                        ^ pz[(floor.z as i64 + k).rem_euclid(256) as usize];
+
+                    let (i, j, k) = (i as Float, j as Float, k as Float);
+                    let offset = Vector::new(fraction.x - i, fraction.y - j, fraction.z - k);
+
+                    accumulator += (i * weights.x + (1.0 - i) * (1.0 - weights.x))
+                        * (j * weights.y + (1.0 - j) * (1.0 - weights.y))
+                        * (k * weights.z + (1.0 - k) * (1.0 - weights.z))
+                        * self.gradients[index].dot(&offset);
+                }
+            }
+        }
+
+        accumulator
+    }
+
+    /// Sum of `depth` octaves of the absolute value of the noise, each with double the frequency and
+    /// half the amplitude of the previous one
+    pub fn turbulence(&self, point: &Point, depth: usize) -> Float {
+        let mut accumulator = 0.0;
+        let mut point = *point;
+        let mut weight = 1.0;
+
+        for _ in 0..depth {
+            accumulator += weight * self.noise(&point).abs();
+            weight *= 0.5;
+            point *= 2.0;
+        }
+
+        accumulator
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoisePattern {
+    /// Plain noise, remapped to [0, 1]
+    Smooth,
+    /// Turbulence with the given number of octaves
+    Turbulence(usize),
+    /// Sine stripes along z, perturbed by turbulence with the given number of octaves
+    Marble(usize),
+}
+
+impl NoisePattern {
+    /// Evaluates the pattern at a point, given the frequency of the noise, returning a value in [0, 1]
+    pub fn intensity(&self, noise: &Perlin, point: &Point, scale: Float) -> Float {
+        let scaled = scale * *point;
+
+        match self {
+            NoisePattern::Smooth => 0.5 * (1.0 + noise.noise(&scaled)),
+            NoisePattern::Turbulence(depth) => noise.turbulence(&scaled, *depth).min(1.0),
+            NoisePattern::Marble(depth) => {
+                0.5 * (1.0 + (scaled.z + 10.0 * noise.turbulence(point, *depth)).sin())
+            }
+        }
+    }
+}
+
+/// A solid texture that modulates a color with Perlin noise
+#[derive(Debug, Clone)]
+pub struct Noise {
+    noise: Perlin,
+    color: Color,
+    scale: Float,
+    pattern: NoisePattern,
+}
+
+impl Noise {
+    /// `scale` is the frequency of the noise: larger values give finer detail
+    pub fn new(color: Color, scale: Float, pattern: NoisePattern) -> Arc<Self> {
+        Arc::new(Self {
+            noise: Perlin::new(),
+            color,
+            scale,
+            pattern,
+        })
+    }
+}
+
+impl Texture for Noise {
+    fn value(&self, _uv: UV, point: &Point) -> Color {
+        self.pattern.intensity(&self.noise, point, self.scale) * self.color
+    }
+}
@@ -0,0 +1,150 @@
+use super::*;
+
+type Matrix3 = nalgebra::Matrix3<Float>;
+
+const MAX_ITERATIONS: usize = 100;
+const CONVERGENCE_TOL: Float = 1e-6;
+
+/// A transform split into a stretch, followed by a rotation and then a translation. Interpolating
+/// these separately keeps objects from shearing or shrinking while they turn.
+#[derive(Debug, Clone, Copy)]
+struct Decomposition {
+    translation: Vector,
+    rotation: Quaternion,
+    stretch: Matrix3,
+}
+
+impl Decomposition {
+    fn new(transform: Transform) -> Self {
+        let matrix = transform.matrix;
+        let translation = Vector::new(matrix[(0, 3)], matrix[(1, 3)], matrix[(2, 3)]);
+        let linear: Matrix3 = matrix.fixed_view::<3, 3>(0, 0).into_owned();
+
+        // Polar decomposition: averaging a matrix with its inverse transpose converges to the closest
+        // orthogonal matrix
+        let mut rotation = linear;
+        for _ in 0..MAX_ITERATIONS {
+            let inverse_transpose = rotation.transpose().try_inverse().unwrap();
+            let next = 0.5 * (rotation + inverse_transpose);
+
+            let change = (next - rotation).abs().max();
+            rotation = next;
+            if change < CONVERGENCE_TOL {
+                break;
+            }
+        }
+
+        // Reflections are left in the stretch, so that what remains is a proper rotation
+        if rotation.determinant() < 0.0 {
+            rotation = -rotation;
+        }
+        let stretch = rotation.transpose() * linear;
+
+        Self {
+            translation,
+            rotation: Quaternion::from_rotation_matrix(
+                &nalgebra::Rotation3::from_matrix_unchecked(rotation),
+            ),
+            stretch,
+        }
+    }
+
+    /// Moves from this decomposition, at a fraction of 0, to the other one, at a fraction of 1
+    fn interpolate(&self, other: &Self, fraction: Float) -> Self {
+        // Opposite rotations have no shortest path between them, so the rotation jumps halfway through
+        let rotation = self
+            .rotation
+            .try_slerp(&other.rotation, fraction, ZERO_TOL)
+            .unwrap_or(if fraction < 0.5 {
+                self.rotation
+            } else {
+                other.rotation
+            });
+
+        Self {
+            translation: (1.0 - fraction) * self.translation + fraction * other.translation,
+            rotation,
+            stretch: (1.0 - fraction) * self.stretch + fraction * other.stretch,
+        }
+    }
+
+    /// Builds the transform along with its inverse, which only takes inverting the stretch
+    fn compose(&self) -> Transform {
+        let rotation = self.rotation.to_rotation_matrix().into_inner();
+        let linear = rotation * self.stretch;
+        let linear_inverse = self.stretch.try_inverse().unwrap() * rotation.transpose();
+        let translation = self.translation.data;
+
+        let mut matrix = linear.to_homogeneous();
+        matrix.fixed_view_mut::<3, 1>(0, 3).copy_from(&translation);
+        let mut inverse = linear_inverse.to_homogeneous();
+        inverse
+            .fixed_view_mut::<3, 1>(0, 3)
+            .copy_from(&(-linear_inverse * translation));
+
+        Transform::from_matrix_inverse(matrix, inverse)
+    }
+
+    fn is_mirrored(&self) -> bool {
+        self.stretch.determinant() < 0.0
+    }
+}
+
+/// A transform that changes over time, interpolating between keyframes
+#[derive(Debug, Clone)]
+pub struct AnimatedTransform {
+    keyframes: Vec<(Float, Decomposition)>,
+    /// The transform at each keyframe, which is all that's needed outside of their span
+    composed: Vec<Transform>,
+}
+
+impl AnimatedTransform {
+    /// Each keyframe pairs a time with the transform at that time. Before the first keyframe and after
+    /// the last one, the transform holds still.
+    /// Will error out if there are no keyframes, or if some transforms are mirrored and others aren't,
+    /// since there's no way to flip between them without collapsing in between
+    pub fn new(keyframes: Vec<(Float, Transform)>) -> Self {
+        assert! { !keyframes.is_empty() }
+
+        let mut keyframes: Vec<_> = keyframes
+            .into_iter()
+            .map(|(time, transform)| (time, Decomposition::new(transform)))
+            .collect();
+        keyframes.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let is_mirrored = keyframes[0].1.is_mirrored();
+        assert! { keyframes.iter().all(|(_, decomposition)| decomposition.is_mirrored() == is_mirrored) }
+
+        let composed = keyframes
+            .iter()
+            .map(|(_, decomposition)| decomposition.compose())
+            .collect();
+
+        Self {
+            keyframes,
+            composed,
+        }
+    }
+
+    /// The times at which the keyframes happen, in increasing order
+    pub fn times(&self) -> Vec<Float> {
+        self.keyframes.iter().map(|(time, _)| *time).collect()
+    }
+
+    pub fn at(&self, time: Float) -> Transform {
+        let next = self.keyframes.partition_point(|(t, _)| *t <= time);
+
+        if next == 0 {
+            return self.composed[0];
+        }
+        if next == self.keyframes.len() {
+            return self.composed[next - 1];
+        }
+
+        let (start, from) = &self.keyframes[next - 1];
+        let (end, to) = &self.keyframes[next];
+        let fraction = (time - start) / (end - start);
+
+        from.interpolate(to, fraction).compose()
+    }
+}
@@ -0,0 +1,66 @@
+use super::*;
+
+/// Composes transforms fluently, in the order they're applied. For instance,
+/// `Transform::builder().scale_all(2.0).rotate_y(90.0).translate(offset).build()` first scales, then
+/// rotates and finally translates.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TransformBuilder {
+    transform: Transform,
+}
+
+impl TransformBuilder {
+    /// Applies an arbitrary transform after the ones so far
+    pub fn then(self, transform: Transform) -> Self {
+        Self {
+            transform: self.transform.then(transform),
+        }
+    }
+
+    pub fn translate(self, offset: Vector) -> Self {
+        self.then(Transform::translate(offset))
+    }
+
+    pub fn scale_all(self, lambda: Float) -> Self {
+        self.then(Transform::scale_all(lambda))
+    }
+
+    pub fn scale(self, x: Float, y: Float, z: Float) -> Self {
+        self.then(Transform::scale(x, y, z))
+    }
+
+    pub fn rotate(self, axis: Vector, angle: Float) -> Self {
+        self.then(Transform::rotate(axis, angle))
+    }
+
+    pub fn rotate_x(self, angle: Float) -> Self {
+        self.then(Transform::rotate_x(angle))
+    }
+
+    pub fn rotate_y(self, angle: Float) -> Self {
+        self.then(Transform::rotate_y(angle))
+    }
+
+    pub fn rotate_z(self, angle: Float) -> Self {
+        self.then(Transform::rotate_z(angle))
+    }
+
+    pub fn euler(self, roll: Float, pitch: Float, yaw: Float) -> Self {
+        self.then(Transform::euler(roll, pitch, yaw))
+    }
+
+    pub fn quaternion(self, rotation: Quaternion) -> Self {
+        self.then(Transform::from_quaternion(rotation))
+    }
+
+    pub fn shear(self, xy: Float, xz: Float, yx: Float, yz: Float, zx: Float, zy: Float) -> Self {
+        self.then(Transform::shear(xy, xz, yx, yz, zx, zy))
+    }
+
+    pub fn look_at(self, from: Point, to: Point, up: Vector) -> Self {
+        self.then(Transform::look_at(from, to, up))
+    }
+
+    pub fn build(self) -> Transform {
+        self.transform
+    }
+}
@@ -5,8 +5,15 @@ use render::Ray;
 type Matrix = nalgebra::Matrix4<Float>;
 type Vec4 = nalgebra::Vector4<Float>;
 
+pub mod animation;
+pub mod builder;
 pub mod ops;
 
+// Reexporting useful types
+pub use animation::AnimatedTransform;
+pub use builder::TransformBuilder;
+pub type Quaternion = nalgebra::UnitQuaternion<Float>;
+
 /// Represents an invertible, affine transform
 #[derive(Debug, Clone, Copy)]
 pub struct Transform {
@@ -60,6 +67,31 @@ impl Transform {
         }
     }
 
+    /// Starts composing a transform out of simpler ones, applied in the order they're given
+    pub fn builder() -> TransformBuilder {
+        TransformBuilder::default()
+    }
+
+    /// Returns the transform that undoes this one
+    pub fn inverse(&self) -> Self {
+        Self {
+            matrix: self.inverse,
+            inverse: self.matrix,
+        }
+    }
+
+    /// Returns the transform that applies this one and then `other`
+    pub fn then(&self, other: Self) -> Self {
+        other * *self
+    }
+
+    /// How much the transform stretches the area around a point of a surface, given the unit normal there
+    pub fn area_scale(&self, normal: Vector) -> Float {
+        let (tangent, bitangent) = normal.orthonormal_basis();
+
+        (*self * tangent).cross(&(*self * bitangent)).norm()
+    }
+
     pub fn translate(offset: Vector) -> Self {
         let id = Matrix::identity();
 
@@ -146,4 +178,98 @@ impl Transform {
 
         Self::from_inverse(inverse)
     }
+
+    /// Returns the transform that maps `u` to (1, 0, 0), `v` to (0, 1, 0) and `w` to (0, 0, 1)
+    /// Will error out if the set {u, v, w} is linearly dependent
+    pub fn make_basis(u: Vector, v: Vector, w: Vector) -> Self {
+        assert! { u.cross(&v).dot(&w).abs() > ZERO_TOL }
+        let inverse = Matrix::from_columns(&[u.unified(), v.unified(), w.unified(), Vec4::w()]);
+
+        Self::from_inverse(inverse)
+    }
+
+    /// Rotates `angle` degrees counterclockwise around `axis`, as seen from its tip.
+    /// Will error out if the axis is the zero vector
+    pub fn rotate(axis: Vector, angle: Float) -> Self {
+        assert! { axis.norm_squared() > ZERO_TOL }
+        let axis = nalgebra::Unit::new_normalize(axis.data);
+
+        Self::from_quaternion(Quaternion::from_axis_angle(
+            &axis,
+            math::degrees_to_radians(angle),
+        ))
+    }
+
+    /// Rotates `angle` degrees counterclockwise around the x axis
+    #[inline]
+    pub fn rotate_x(angle: Float) -> Self {
+        Self::rotate(Vector::x(), angle)
+    }
+
+    /// Rotates `angle` degrees counterclockwise around the y axis
+    #[inline]
+    pub fn rotate_y(angle: Float) -> Self {
+        Self::rotate(Vector::y(), angle)
+    }
+
+    /// Rotates `angle` degrees counterclockwise around the z axis
+    #[inline]
+    pub fn rotate_z(angle: Float) -> Self {
+        Self::rotate(Vector::z(), angle)
+    }
+
+    /// Rotates by Euler angles in degrees, applying the roll around x first, then the pitch around y and
+    /// lastly the yaw around z
+    pub fn euler(roll: Float, pitch: Float, yaw: Float) -> Self {
+        Self::from_quaternion(Quaternion::from_euler_angles(
+            math::degrees_to_radians(roll),
+            math::degrees_to_radians(pitch),
+            math::degrees_to_radians(yaw),
+        ))
+    }
+
+    /// Makes the rotation represented by a unit quaternion
+    pub fn from_quaternion(rotation: Quaternion) -> Self {
+        let matrix = rotation.to_homogeneous();
+
+        // Rotations are orthogonal, so the transpose is the inverse
+        Self {
+            matrix,
+            inverse: matrix.transpose(),
+        }
+    }
+
+    /// Returns the transform that places an object at `from`, with its -z axis pointing towards `to`
+    /// and its y axis as close to `up` as possible, which is the convention the cameras use.
+    /// Will error out if `from` and `to` coincide, or if `up` is parallel to the viewing direction
+    pub fn look_at(from: Point, to: Point, up: Vector) -> Self {
+        let w = from - to;
+        assert! { w.norm_squared() > ZERO_TOL }
+        let w = w.normalize();
+
+        let u = up.cross(&w);
+        assert! { u.norm_squared() > ZERO_TOL }
+        let u = u.normalize();
+        let v = w.cross(&u);
+
+        let rotation = Matrix::from_columns(&[u.unified(), v.unified(), w.unified(), Vec4::w()]);
+        let matrix = Self::translate(from.into()).matrix * rotation;
+        let inverse = rotation.transpose() * Self::make_origin(from).matrix;
+
+        Self { matrix, inverse }
+    }
+
+    /// Makes a shear, where each coordinate gets a multiple of the other two added to it. For instance,
+    /// `xy` is how much of y is added to x.
+    /// Will error out if the resulting transform isn't invertible
+    pub fn shear(xy: Float, xz: Float, yx: Float, yz: Float, zx: Float, zy: Float) -> Self {
+        let matrix = Matrix::new(
+            1.0, xy, xz, 0.0, //
+            yx, 1.0, yz, 0.0, //
+            zx, zy, 1.0, 0.0, //
+            0.0, 0.0, 0.0, 1.0,
+        );
+
+        Self::from_matrix(matrix)
+    }
 }
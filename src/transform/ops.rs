@@ -23,12 +23,24 @@ impl Mul<Vector> for Transform {
     }
 }
 
+impl Mul<Normal> for Transform {
+    type Output = Normal;
+
+    /// Normals are transformed by the inverse-transpose, which is why the inverse appears here
+    #[inline]
+    fn mul(self, rhs: Normal) -> Self::Output {
+        Self::Output {
+            data: (self.inverse.transpose() * rhs.unified()).xyz(),
+        }
+    }
+}
+
 impl Mul<Ray> for Transform {
     type Output = Ray;
 
     #[inline]
     fn mul(self, rhs: Ray) -> Self::Output {
-        Ray::new(self * rhs.origin, self * rhs.direction)
+        rhs.spawn(self * rhs.origin, self * rhs.direction)
     }
 }
 
@@ -69,12 +81,25 @@ impl Div<Vector> for Transform {
     }
 }
 
+// Multiplication is allowed here because we cache the inverse
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Div<Normal> for Transform {
+    type Output = Normal;
+
+    #[inline]
+    fn div(self, rhs: Normal) -> Self::Output {
+        Self::Output {
+            data: (self.matrix.transpose() * rhs.unified()).xyz(),
+        }
+    }
+}
+
 impl Div<Ray> for Transform {
     type Output = Ray;
 
     #[inline]
     fn div(self, rhs: Ray) -> Self::Output {
-        Ray::new(self / rhs.origin, self / rhs.direction)
+        rhs.spawn(self / rhs.origin, self / rhs.direction)
     }
 }
 
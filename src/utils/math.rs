@@ -3,12 +3,57 @@ use super::*;
 pub const PI: Float = std::f32::consts::PI;
 pub const ZERO_TOL: Float = 1e-8;
 
+/// Schlick's approximation of the Fresnel reflectance, given the reflectance at normal incidence and the
+/// cosine of the angle between the incoming direction and the normal
 #[inline]
 pub fn schlick(r0: Float, cos_theta: Float) -> Float {
-    r0 * (1.0 - r0) * (1.0 - cos_theta).powi(5)
+    r0 + (1.0 - r0) * (1.0 - cos_theta).powi(5)
+}
+
+/// Fraction of unpolarized light reflected by the boundary between two dielectrics, given the cosine
+/// of the angle of incidence and the ratio between the refractive index on the incoming side and the one
+/// on the other side. Returns 1 under total internal reflection.
+pub fn fresnel_dielectric(cos_incident: Float, ratio: Float) -> Float {
+    let cos_incident = cos_incident.clamp(0.0, 1.0);
+    let sin_transmitted_squared = ratio * ratio * (1.0 - cos_incident * cos_incident);
+    if sin_transmitted_squared >= 1.0 {
+        return 1.0;
+    }
+
+    let cos_transmitted = (1.0 - sin_transmitted_squared).sqrt();
+    let perpendicular =
+        (ratio * cos_incident - cos_transmitted) / (ratio * cos_incident + cos_transmitted);
+    let parallel =
+        (cos_incident - ratio * cos_transmitted) / (cos_incident + ratio * cos_transmitted);
+
+    0.5 * (perpendicular * perpendicular + parallel * parallel)
 }
 
 #[inline]
 pub fn degrees_to_radians(theta: Float) -> Float {
     theta * PI / 180.0
 }
+
+/// Fraction of unpolarized light reflected by a conductor with complex refractive index eta + ik, given
+/// the cosine of the angle of incidence
+pub fn fresnel_conductor(cos_incident: Float, eta: Float, k: Float) -> Float {
+    let cos_squared = cos_incident.clamp(0.0, 1.0).powi(2);
+    let sin_squared = 1.0 - cos_squared;
+    let (eta_squared, k_squared) = (eta * eta, k * k);
+
+    let difference = eta_squared - k_squared - sin_squared;
+    let a_squared_plus_b_squared = (difference * difference + 4.0 * eta_squared * k_squared).sqrt();
+    let a = (0.5 * (a_squared_plus_b_squared + difference))
+        .max(0.0)
+        .sqrt();
+
+    let sum = a_squared_plus_b_squared + cos_squared;
+    let cross = 2.0 * cos_incident.clamp(0.0, 1.0) * a;
+    let perpendicular = (sum - cross) / (sum + cross);
+
+    let sum = cos_squared * a_squared_plus_b_squared + sin_squared * sin_squared;
+    let cross = cross * sin_squared;
+    let parallel = perpendicular * (sum - cross) / (sum + cross);
+
+    0.5 * (perpendicular + parallel)
+}
@@ -2,5 +2,7 @@ use super::*;
 
 pub mod math;
 pub mod parallelization;
+pub mod polynomial;
 pub mod random;
+pub mod spectrum;
 pub mod types;
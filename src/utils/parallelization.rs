@@ -43,7 +43,7 @@ fn separate_lines(
 /// Joins canvases vertically based on the y_offset of each tile.
 /// Assumes that each band was created with the division strategy of determine_work
 pub fn glue_canvases(mut canvases: Vec<(usize, PngTile)>) -> PngTile {
-    canvases.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+    canvases.sort_unstable_by_key(|(a, _)| *a);
 
     canvases
         .into_iter()
@@ -0,0 +1,239 @@
+// Real root finding for polynomials of degree up to four, in double precision since the quartics coming
+// from ray intersections are badly conditioned. Coefficients are given from the highest degree down and
+// roots are returned in increasing order.
+
+const EPSILON: f64 = 1e-12;
+
+/// Solves ax² + bx + c = 0, degrading to a linear equation when a is 0
+pub fn solve_quadratic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if a.abs() < EPSILON {
+        return if b.abs() < EPSILON {
+            vec![]
+        } else {
+            vec![-c / b]
+        };
+    }
+
+    let delta = b * b - 4.0 * a * c;
+    if delta < 0.0 {
+        return vec![];
+    }
+
+    // Avoids the cancellation of subtracting two close numbers
+    let q = -0.5 * (b + delta.sqrt().copysign(b));
+    let mut roots = if q.abs() < EPSILON {
+        vec![0.0, 0.0]
+    } else {
+        vec![q / a, c / q]
+    };
+    roots.sort_unstable_by(f64::total_cmp);
+
+    roots
+}
+
+/// Solves ax³ + bx² + cx + d = 0, degrading to a quadratic equation when a is 0
+pub fn solve_cubic(a: f64, b: f64, c: f64, d: f64) -> Vec<f64> {
+    if a.abs() < EPSILON {
+        return solve_quadratic(b, c, d);
+    }
+
+    // Depressing x³ + Ax² + Bx + C into t³ + Pt + Q, where x = t - A/3
+    let (b, c, d) = (b / a, c / a, d / a);
+    let shift = b / 3.0;
+    let p = c - b * shift;
+    let q = 2.0 * shift * shift * shift - shift * c + d;
+
+    let discriminant = q * q / 4.0 + p * p * p / 27.0;
+    let mut roots = if discriminant > 0.0 {
+        let sqrt = discriminant.sqrt();
+        vec![(-q / 2.0 + sqrt).cbrt() + (-q / 2.0 - sqrt).cbrt()]
+    } else if p.abs() < EPSILON {
+        vec![0.0]
+    } else {
+        // Three real roots, found through the trigonometric method
+        let radius = 2.0 * (-p / 3.0).sqrt();
+        let angle = ((3.0 * q / (2.0 * p)) * (-3.0 / p).sqrt())
+            .clamp(-1.0, 1.0)
+            .acos()
+            / 3.0;
+
+        (0..3)
+            .map(|k| radius * (angle - 2.0 * std::f64::consts::PI * k as f64 / 3.0).cos())
+            .collect()
+    };
+
+    for root in roots.iter_mut() {
+        *root = polish(&[1.0, b, c, d], *root - shift);
+    }
+    roots.sort_unstable_by(f64::total_cmp);
+
+    roots
+}
+
+/// Solves ax⁴ + bx³ + cx² + dx + e = 0 through Ferrari's method, degrading to a cubic equation when a is 0
+pub fn solve_quartic(a: f64, b: f64, c: f64, d: f64, e: f64) -> Vec<f64> {
+    if a.abs() < EPSILON {
+        return solve_cubic(b, c, d, e);
+    }
+
+    // Depressing x⁴ + Ax³ + Bx² + Cx + D into y⁴ + Py² + Qy + R, where x = y - A/4
+    let (b, c, d, e) = (b / a, c / a, d / a, e / a);
+    let shift = b / 4.0;
+    let shift_sq = shift * shift;
+    let p = c - 6.0 * shift_sq;
+    let q = d - 2.0 * c * shift + 8.0 * shift_sq * shift;
+    let r = e - d * shift + c * shift_sq - 3.0 * shift_sq * shift_sq;
+
+    let mut roots = if q.abs() < EPSILON {
+        // Biquadratic, i.e.: a quadratic in y²
+        solve_quadratic(1.0, p, r)
+            .into_iter()
+            .filter(|&z| z >= 0.0)
+            .flat_map(|z| [-z.sqrt(), z.sqrt()])
+            .collect()
+    } else {
+        // Any positive root m of the resolvent cubic splits the quartic into two quadratics
+        let m = solve_cubic(1.0, p, p * p / 4.0 - r, -q * q / 8.0)
+            .into_iter()
+            .fold(0.0, f64::max);
+        if m <= 0.0 {
+            return vec![];
+        }
+
+        let s = (2.0 * m).sqrt();
+        let mut roots = solve_quadratic(1.0, -s, p / 2.0 + m + q / (2.0 * s));
+        roots.extend(solve_quadratic(1.0, s, p / 2.0 + m - q / (2.0 * s)));
+
+        roots
+    };
+
+    for root in roots.iter_mut() {
+        *root = polish(&[1.0, b, c, d, e], *root - shift);
+    }
+    roots.sort_unstable_by(f64::total_cmp);
+
+    roots
+}
+
+/// Refines a root through a few Newton iterations, keeping the original guess if they diverge
+fn polish(coefficients: &[f64], guess: f64) -> f64 {
+    let evaluate = |x: f64| {
+        coefficients
+            .iter()
+            .fold((0.0, 0.0), |(value, derivative), coefficient| {
+                (value * x + coefficient, derivative * x + value)
+            })
+    };
+
+    let mut root = guess;
+    for _ in 0..4 {
+        let (value, derivative) = evaluate(root);
+        if derivative.abs() < EPSILON {
+            break;
+        }
+        root -= value / derivative;
+    }
+
+    if root.is_finite() && evaluate(root).0.abs() <= evaluate(guess).0.abs() {
+        root
+    } else {
+        guess
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_roots(found: Vec<f64>, expected: &[f64], tolerance: f64) {
+        assert_eq!(
+            found.len(),
+            expected.len(),
+            "found {found:?}, expected {expected:?}"
+        );
+        for (root, expected_root) in found.iter().zip(expected) {
+            assert!(
+                (root - expected_root).abs() <= tolerance,
+                "found {found:?}, expected {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn quadratic() {
+        assert_roots(solve_quadratic(1.0, -4.0, 3.0), &[1.0, 3.0], 1e-12);
+        assert_roots(solve_quadratic(-2.0, 0.0, 8.0), &[-2.0, 2.0], 1e-12);
+        assert_roots(solve_quadratic(1.0, 0.0, 1.0), &[], 0.0);
+        assert_roots(solve_quadratic(0.0, 2.0, -1.0), &[0.5], 1e-12);
+    }
+
+    #[test]
+    fn quadratic_double_root() {
+        assert_roots(solve_quadratic(1.0, -4.0, 4.0), &[2.0, 2.0], 1e-12);
+        assert_roots(solve_quadratic(1.0, 0.0, 0.0), &[0.0, 0.0], 1e-12);
+    }
+
+    #[test]
+    fn quadratic_without_cancellation() {
+        // The small root is lost when computed as (-b - sqrt(delta)) / 2a
+        let roots = solve_quadratic(1.0, -1e8, 1.0);
+        assert_roots(roots.clone(), &[1e-8, 1e8], 1e-6);
+        assert!((roots[0] - 1e-8).abs() < 1e-20);
+    }
+
+    #[test]
+    fn cubic() {
+        // (x - 1)(x - 2)(x - 3)
+        assert_roots(solve_cubic(1.0, -6.0, 11.0, -6.0), &[1.0, 2.0, 3.0], 1e-9);
+        // (x - 2)(x² + 1)
+        assert_roots(solve_cubic(2.0, -4.0, 2.0, -4.0), &[2.0], 1e-9);
+        assert_roots(solve_cubic(1.0, 0.0, 0.0, -8.0), &[2.0], 1e-9);
+        assert_roots(solve_cubic(0.0, 1.0, -4.0, 3.0), &[1.0, 3.0], 1e-12);
+    }
+
+    #[test]
+    fn cubic_repeated_roots() {
+        // (x - 1)²(x + 2)
+        assert_roots(solve_cubic(1.0, 0.0, -3.0, 2.0), &[-2.0, 1.0, 1.0], 1e-6);
+        // (x - 1)³
+        assert_roots(solve_cubic(1.0, -3.0, 3.0, -1.0), &[1.0], 1e-6);
+    }
+
+    #[test]
+    fn quartic() {
+        // (x - 1)(x - 2)(x - 3)(x - 4)
+        let roots = solve_quartic(1.0, -10.0, 35.0, -50.0, 24.0);
+        assert_roots(roots, &[1.0, 2.0, 3.0, 4.0], 1e-9);
+        // (x + 1)(x - 2)(x² + 1)
+        let roots = solve_quartic(3.0, -3.0, -3.0, -3.0, -6.0);
+        assert_roots(roots, &[-1.0, 2.0], 1e-9);
+        assert_roots(solve_quartic(1.0, 0.0, 0.0, 0.0, 1.0), &[], 0.0);
+        assert_roots(
+            solve_quartic(0.0, 1.0, -6.0, 11.0, -6.0),
+            &[1.0, 2.0, 3.0],
+            1e-9,
+        );
+    }
+
+    #[test]
+    fn depressed_quartic() {
+        // Biquadratic (x² - 1)(x² - 4)
+        let roots = solve_quartic(1.0, 0.0, -5.0, 0.0, 4.0);
+        assert_roots(roots, &[-2.0, -1.0, 1.0, 2.0], 1e-9);
+        // (x² - 1)(x² + 4), whose negative y² is dropped
+        assert_roots(solve_quartic(1.0, 0.0, 3.0, 0.0, -4.0), &[-1.0, 1.0], 1e-9);
+        // (x - 1)(x - 2)(x - 3)(x + 6), already free of the cubic term
+        let roots = solve_quartic(1.0, 0.0, -25.0, 60.0, -36.0);
+        assert_roots(roots, &[-6.0, 1.0, 2.0, 3.0], 1e-9);
+    }
+
+    #[test]
+    fn quartic_double_root() {
+        // (x - 1)²(x - 3)(x + 2)
+        let roots = solve_quartic(1.0, -3.0, -3.0, 11.0, -6.0);
+        assert_roots(roots, &[-2.0, 1.0, 1.0, 3.0], 1e-6);
+        // (x - 1)²(x + 1)², tangent to the axis at both roots
+        let roots = solve_quartic(1.0, 0.0, -2.0, 0.0, 1.0);
+        assert_roots(roots, &[-1.0, -1.0, 1.0, 1.0], 1e-6);
+    }
+}
@@ -0,0 +1,72 @@
+// Conversions between wavelengths of visible light, in nanometers, and colors
+
+use super::*;
+
+/// Shortest wavelength that's rendered
+pub const MIN_WAVELENGTH: Float = 380.0;
+/// Longest wavelength that's rendered
+pub const MAX_WAVELENGTH: Float = 780.0;
+/// The yellow Fraunhofer d line, at which refractive indices are usually quoted
+pub const REFERENCE_WAVELENGTH: Float = 587.56;
+
+/// Gaussian with a different spread on either side of the mean
+#[inline]
+fn lobe(wavelength: Float, mean: Float, left_spread: Float, right_spread: Float) -> Float {
+    let spread = if wavelength < mean {
+        left_spread
+    } else {
+        right_spread
+    };
+    let offset = (wavelength - mean) / spread;
+
+    (-0.5 * offset * offset).exp()
+}
+
+/// The CIE 1931 color matching functions, through the multi-lobe fit by Wyman, Sloan and Shirley
+pub fn wavelength_to_xyz(wavelength: Float) -> Vector {
+    let x = 1.056 * lobe(wavelength, 599.8, 37.9, 31.0)
+        + 0.362 * lobe(wavelength, 442.0, 16.0, 26.7)
+        - 0.065 * lobe(wavelength, 501.1, 20.4, 26.2);
+    let y =
+        0.821 * lobe(wavelength, 568.8, 46.9, 40.5) + 0.286 * lobe(wavelength, 530.9, 16.3, 31.1);
+    let z =
+        1.217 * lobe(wavelength, 437.0, 11.8, 36.0) + 0.681 * lobe(wavelength, 459.0, 26.0, 13.8);
+
+    Vector::new(x, y, z)
+}
+
+/// Converts CIE XYZ to linear sRGB, which may have negative channels for colors outside its gamut
+pub fn xyz_to_rgb(xyz: Vector) -> Color {
+    let Vector { data } = xyz;
+
+    Color::new(
+        3.240_454 * data.x - 1.537_139 * data.y - 0.498_531 * data.z,
+        -0.969_266 * data.x + 1.876_011 * data.y + 0.041_556 * data.z,
+        0.055_643 * data.x - 0.204_026 * data.y + 1.057_225 * data.z,
+    )
+}
+
+/// Linear sRGB response to light of a single wavelength
+#[inline]
+pub fn wavelength_to_rgb(wavelength: Float) -> Color {
+    xyz_to_rgb(wavelength_to_xyz(wavelength))
+}
+
+/// Wavelengths around which the blue, green and red primaries dominate
+const PRIMARY_WAVELENGTHS: [Float; 3] = [450.0, 540.0, 610.0];
+
+/// Spreads the channels of a color over the spectrum, interpolating between the wavelengths where each
+/// primary dominates. Gray stays flat, which suits coefficients such as absorption.
+pub fn color_at(color: Color, wavelength: Float) -> Float {
+    let [blue, green, red] = PRIMARY_WAVELENGTHS;
+    let lerp = |from: Float, to: Float, start: Float, end: Float| {
+        let fraction = ((wavelength - start) / (end - start)).clamp(0.0, 1.0);
+        from + (to - from) * fraction
+    };
+
+    if wavelength < green {
+        lerp(color.b, color.g, blue, green)
+    } else {
+        lerp(color.g, color.r, green, red)
+    }
+}
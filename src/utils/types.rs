@@ -55,3 +55,7 @@ impl Range {
         Self(self.0 + padding, self.1 + padding)
     }
 }
+
+/// Surface parameters, usually in [0, 1]², used to look up textures
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct UV(pub Float, pub Float);